@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use crate::scoring::{is_consistent, score_pattern};
+use crate::game::Turn;
+use crate::solver::prepare;
+
+// --- Looking back at a finished game ---
+pub struct GuessAnalysis {
+    pub guess: String,
+    /// Words that could still have been the answer before and after the guess
+    pub before: usize,
+    pub after: usize,
+    /// Information the feedback actually gave, in bits
+    pub bits: f64,
+    /// The guess that was expected to give the most information, and how much it would have
+    pub best_guess: String,
+    pub best_expected_bits: f64,
+    /// How much information the guess was expected to give, compared to the best guess
+    pub skill: f64,
+    /// How likely it was to get worse feedback than what the guess actually got
+    pub luck: f64,
+}
+
+/// Goes through the game guess by guess. `words` are the valid words of the answer's length.
+pub fn analyze(
+    answer: &str,
+    history: &[Turn],
+    words: &[String],
+    matrix: bool,
+) -> Result<Vec<GuessAnalysis>, String> {
+    // The matrix is for the list as it is, an answer that isn't in it is scored the slow way
+    let mut solver = prepare("entropy", words, matrix)?;
+    let mut words = words.to_vec();
+    if !words.iter().any(|w| w == answer) {
+        words.push(answer.to_string());
+    }
+    let mut candidates = words.clone();
+    let mut analysis = Vec::new();
+
+    for (n, turn) in history.iter().enumerate() {
+        let best_guess = solver.next_guess(&words, &candidates, &history[..n])?;
+        let best_expected_bits = expected_information(&best_guess, &candidates);
+        let expected_bits = expected_information(&turn.guess, &candidates);
+
+        let before = candidates.len();
+        let outcomes = outcome_sizes(&turn.guess, &candidates);
+        candidates.retain(|w| is_consistent(w, &turn.guess, &turn.feedback));
+        let after = candidates.len();
+
+        // Every candidate is an equally likely answer, count the ones that would have left more words
+        let worse = outcomes.iter().filter(|&&size| size > after).count();
+        let equal = outcomes.iter().filter(|&&size| size == after).count();
+        let luck = (worse as f64 + equal as f64 / 2.0) / before as f64;
+        let skill = if best_expected_bits > 0.0 {
+            (expected_bits / best_expected_bits).min(1.0)
+        } else {
+            1.0
+        };
+
+        analysis.push(GuessAnalysis {
+            guess: turn.guess.clone(),
+            before,
+            after,
+            bits: (before as f64 / after.max(1) as f64).log2(),
+            best_guess,
+            best_expected_bits,
+            skill,
+            luck,
+        });
+    }
+    Ok(analysis)
+}
+
+/// For every candidate, how many candidates would be left if it was the answer.
+fn outcome_sizes(guess: &str, candidates: &[String]) -> Vec<usize> {
+    let patterns: Vec<_> = candidates.iter().map(|c| score_pattern(c, guess)).collect();
+    let mut groups: HashMap<_, usize> = HashMap::new();
+    for pattern in &patterns {
+        *groups.entry(*pattern).or_default() += 1;
+    }
+    patterns.iter().map(|p| groups[p]).collect()
+}
+
+/// How many bits of information the guess is expected to give about which candidate is the answer.
+pub fn expected_information(guess: &str, candidates: &[String]) -> f64 {
+    // The sizes are summed in the order the patterns are first seen, so the result is the same
+    // every run
+    let mut groups: HashMap<_, usize> = HashMap::new();
+    let mut sizes: Vec<usize> = Vec::new();
+    for candidate in candidates {
+        let index = *groups.entry(score_pattern(candidate, guess)).or_insert_with(|| {
+            sizes.push(0);
+            sizes.len() - 1
+        });
+        sizes[index] += 1;
+    }
+    let total = candidates.len() as f64;
+    sizes
+        .iter()
+        .map(|&size| {
+            let p = size as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+pub fn print_analysis(analysis: &[GuessAnalysis]) {
+    println!(
+        "\n{:<12} {:>8} {:>8} {:>6}   {:<12} {:>6} {:>6}",
+        "Guess", "Before", "After", "Bits", "Best guess", "Skill", "Luck"
+    );
+    for a in analysis {
+        println!(
+            "{:<12} {:>8} {:>8} {:>6.2}   {:<12} {:>5.0}% {:>5.0}%",
+            a.guess,
+            a.before,
+            a.after,
+            a.bits,
+            format!("{} ({:.1})", a.best_guess, a.best_expected_bits),
+            a.skill * 100.0,
+            a.luck * 100.0
+        );
+    }
+    if analysis.is_empty() {
+        return;
+    }
+
+    let count = analysis.len() as f64;
+    let skill = analysis.iter().map(|a| a.skill).sum::<f64>() / count;
+    let luck = analysis.iter().map(|a| a.luck).sum::<f64>() / count;
+    let verdict = match (skill >= 0.8, luck >= 0.5) {
+        (true, true) => "Skilled, and the letters were on your side. 😎",
+        (true, false) => "Well played, the feedback just didn't cooperate. 💪",
+        (false, true) => "The dice rolled your way this time. 🍀",
+        (false, false) => "Tough game, there's room to pick sharper guesses. 🧐",
+    };
+    println!(
+        "Skill {:.0}%, luck {:.0}%. {}",
+        skill * 100.0,
+        luck * 100.0,
+        verdict
+    );
+}
@@ -0,0 +1,100 @@
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::solver::{by_name, matrix_for, solve};
+
+const WORST_CASE_SHOWN: usize = 10;
+const HISTOGRAM_WIDTH: usize = 50;
+// Solvers that haven't found the word by now probably never will
+const GIVE_UP_AFTER: u32 = 100;
+
+/// Runs the strategy against every word in the list, in parallel, and prints a report.
+pub fn run(strategy: &str, matrix: bool, words: &[String], guesses: u32) -> Result<(), String> {
+    // Only words of the same length can be candidates for each other
+    let mut by_length: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for word in words {
+        by_length
+            .entry(word.chars().count())
+            .or_default()
+            .push(word.clone());
+    }
+
+    println!("Solving {} words...", words.len());
+    let mut results: Vec<(String, Option<u32>)> = Vec::with_capacity(words.len());
+    for words in by_length.values() {
+        // Every thread gets its own solver, but make sure the name is fine before starting
+        by_name(strategy)?;
+        let matrix = matrix_for(words, matrix);
+        let solved: Result<Vec<_>, String> = words
+            .par_iter()
+            .map_init(
+                || {
+                    let mut solver = by_name(strategy).unwrap();
+                    if let Some(matrix) = &matrix {
+                        solver.use_matrix(matrix.clone());
+                    }
+                    solver
+                },
+                |solver, answer| {
+                    solve(solver.as_mut(), answer, words, GIVE_UP_AFTER).map(|result| (answer.clone(), result))
+                },
+            )
+            .collect();
+        results.extend(solved?);
+    }
+    if results.is_empty() {
+        println!("The word list is empty, nothing to solve.");
+        return Ok(());
+    }
+
+    let (results, unsolved): (Vec<_>, Vec<_>) = results.into_iter().partition(|(_, n)| n.is_some());
+    if !unsolved.is_empty() {
+        let words: Vec<&str> = unsolved.iter().map(|(w, _)| w.as_str()).collect();
+        println!(
+            "Gave up on {} words after {} guesses: {}",
+            unsolved.len(),
+            GIVE_UP_AFTER,
+            words[..words.len().min(WORST_CASE_SHOWN)].join(", ")
+        );
+    }
+    let mut results: Vec<(String, u32)> = results
+        .into_iter()
+        .map(|(word, n)| (word, n.unwrap()))
+        .collect();
+    if results.is_empty() {
+        return Ok(());
+    }
+    let failures = unsolved.len() + results.iter().filter(|(_, n)| *n > guesses).count();
+    let attempted = results.len() + unsolved.len();
+
+    let total: u64 = results.iter().map(|(_, n)| *n as u64).sum();
+    println!(
+        "Average guesses: {:.3}",
+        total as f64 / results.len() as f64
+    );
+    println!(
+        "Failures: {} out of {} ({:.2}%) with {} guesses",
+        failures,
+        attempted,
+        failures as f64 * 100.0 / attempted as f64,
+        guesses
+    );
+
+    let mut distribution: BTreeMap<u32, usize> = BTreeMap::new();
+    for (_, n) in &results {
+        *distribution.entry(*n).or_default() += 1;
+    }
+    let most = *distribution.values().max().unwrap();
+    println!("\nDistribution:");
+    for (n, count) in &distribution {
+        let bar = "█".repeat((count * HISTOGRAM_WIDTH).div_ceil(most));
+        println!("{:>3} | {} {}", n, bar, count);
+    }
+
+    results.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    println!("\nWorst case words:");
+    for (word, n) in results.iter().take(WORST_CASE_SHOWN) {
+        println!("{:>3}  {}", n, word);
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::collections::HashSet;
+use std::fs;
+
+// --- Words that are never picked as the answer ---
+//
+// Two blocklists come with rustle: names of people and places, and words you wouldn't want to
+// spring on your boss or your kids. --family-friendly uses both, and --blocklist adds more, either
+// one of the bundled ones by name or a file with one word per line. Blocked words can still be
+// guessed, they just never come up as the answer.
+
+const PROPER_NOUNS: &str = include_str!("../word_lists/blocklists/proper_nouns.txt");
+const OFFENSIVE: &str = include_str!("../word_lists/blocklists/offensive.txt");
+
+const BUNDLED: [(&str, &str); 2] = [("proper-nouns", PROPER_NOUNS), ("offensive", OFFENSIVE)];
+
+/// The words of a blocklist. Lines starting with `#` are comments.
+fn parse(raw: &str) -> impl Iterator<Item = String> + '_ {
+    raw.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_lowercase)
+}
+
+/// All the blocked words: both bundled lists if `family_friendly`, and every list in `lists`,
+/// which can be the name of a bundled list or a file.
+pub fn load(family_friendly: bool, lists: &[String]) -> Result<HashSet<String>, String> {
+    let mut blocked = HashSet::new();
+    if family_friendly {
+        for (_, raw) in BUNDLED {
+            blocked.extend(parse(raw));
+        }
+    }
+    for list in lists {
+        match BUNDLED.iter().find(|(name, _)| name == list) {
+            Some((_, raw)) => blocked.extend(parse(raw)),
+            None => {
+                let raw = fs::read_to_string(list).map_err(|e| format!("Can't read the blocklist {}: {}", list, e))?;
+                blocked.extend(parse(&raw));
+            }
+        }
+    }
+    Ok(blocked)
+}
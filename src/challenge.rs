@@ -0,0 +1,170 @@
+use rand::Rng;
+
+// --- Challenge codes, for sharing a word without spoiling it ---
+const CODE_VERSION: u8 = 1;
+const CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const GROUP_SIZE: usize = 5;
+
+/// The classic game, the only mode so far
+pub const MODE_CLASSIC: u8 = 0;
+
+pub struct Challenge {
+    pub word: String,
+    /// The low 32 bits of the word list hash, to tell if the friend uses the same list
+    pub list_hash: u32,
+    pub guesses: u32,
+    pub mode: u8,
+}
+
+impl Challenge {
+    /// Packs the challenge into a code like `7K2QD-M0X4R-...`. The bytes are scrambled with a
+    /// random salt, so the same word gives a different code every time and can't be read from it.
+    pub fn encode(&self) -> String {
+        let mut data = vec![
+            CODE_VERSION,
+            self.mode,
+            self.guesses.min(255) as u8,
+        ];
+        data.extend_from_slice(&self.list_hash.to_le_bytes());
+        data.extend_from_slice(self.word.as_bytes());
+        data.extend_from_slice(&checksum(&data).to_le_bytes());
+
+        let salt: u8 = rand::thread_rng().gen();
+        scramble(&mut data, salt);
+        data.insert(0, salt);
+
+        let code = to_base32(&data);
+        code.as_bytes()
+            .chunks(GROUP_SIZE)
+            .map(|group| String::from_utf8_lossy(group).to_string())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    pub fn decode(code: &str) -> Result<Challenge, String> {
+        let invalid = || String::from("That challenge code isn't valid, check it for typos. 🧐");
+        let mut data = from_base32(code).ok_or_else(invalid)?;
+        if data.len() < 12 {
+            return Err(invalid());
+        }
+        let salt = data.remove(0);
+        scramble(&mut data, salt);
+
+        let (body, sum) = data.split_at(data.len() - 4);
+        if checksum(body).to_le_bytes() != sum {
+            return Err(invalid());
+        }
+        if body[0] != CODE_VERSION || body[1] != MODE_CLASSIC {
+            return Err(String::from(
+                "That challenge was made by a newer version of rustle, try updating.",
+            ));
+        }
+        let word = String::from_utf8(body[7..].to_vec()).map_err(|_| invalid())?;
+        if word.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Challenge {
+            word,
+            list_hash: u32::from_le_bytes([body[3], body[4], body[5], body[6]]),
+            guesses: body[2] as u32,
+            mode: body[1],
+        })
+    }
+}
+
+/// FNV-1a, 32 bits.
+fn checksum(data: &[u8]) -> u32 {
+    data.iter().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x01000193)
+    })
+}
+
+/// XORs the data with a keystream from the salt. Doing it twice gives back the original.
+fn scramble(data: &mut [u8], salt: u8) {
+    let mut state: u32 = 0x52_55_53_54 ^ (salt as u32).wrapping_mul(0x9e3779b9);
+    for byte in data.iter_mut() {
+        // xorshift32
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        *byte ^= (state >> 24) as u8;
+    }
+}
+
+fn to_base32(data: &[u8]) -> String {
+    let mut code = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            code.push(CODE_ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        code.push(CODE_ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    code
+}
+
+/// Reads the code back, ignoring dashes and case. O and I/L are read as 0 and 1, like Crockford's
+/// base 32 does, because they are easy to mix up when typing a code over.
+fn from_base32(code: &str) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in code.chars().filter(|c| !matches!(c, '-' | ' ')) {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let value = CODE_ALPHABET.iter().position(|a| *a as char == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((buffer >> bits) as u8);
+        }
+    }
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenge() -> Challenge {
+        Challenge {
+            word: String::from("crane"),
+            list_hash: 0xdeadbeef,
+            guesses: 6,
+            mode: MODE_CLASSIC,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let code = challenge().encode();
+        let decoded = Challenge::decode(&code).unwrap();
+        assert_eq!(decoded.word, "crane");
+        assert_eq!(decoded.list_hash, 0xdeadbeef);
+        assert_eq!(decoded.guesses, 6);
+        assert_eq!(decoded.mode, MODE_CLASSIC);
+        // Typed over by hand
+        assert_eq!(Challenge::decode(&code.to_lowercase().replace('0', "o")).unwrap().word, "crane");
+    }
+
+    #[test]
+    fn tampered_codes_are_rejected() {
+        let code = challenge().encode();
+        let mut tampered: Vec<char> = code.chars().collect();
+        tampered[7] = if tampered[7] == 'A' { 'B' } else { 'A' };
+        let tampered: String = tampered.into_iter().collect();
+        assert!(Challenge::decode(&tampered).is_err());
+        assert!(Challenge::decode(&code[..code.len() - 6]).is_err());
+        assert!(Challenge::decode("not a code!").is_err());
+    }
+}
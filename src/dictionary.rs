@@ -0,0 +1,157 @@
+use serde::Deserialize;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+// --- Looking up words in a dictionary on disk ---
+//
+// Three kinds of dictionaries work:
+//   - a WordNet dict folder, or one of its data.noun, data.verb, data.adj or data.adv files
+//   - a Wiktionary extract as JSON lines, like the ones from kaikki.org (.jsonl or .json)
+//   - a tab separated file with a word, its part of speech and a definition on every line
+//
+// Dictionaries can be huge, so they're searched line by line for every lookup instead of loaded.
+
+const WORDNET_FILES: [&str; 4] = ["data.noun", "data.verb", "data.adj", "data.adv"];
+// Enough to know what the word means, without filling the screen
+pub const MAX_DEFINITIONS: usize = 3;
+
+pub struct Definition {
+    pub part_of_speech: String,
+    pub text: String,
+}
+
+#[derive(Deserialize)]
+struct WiktionaryEntry {
+    word: String,
+    #[serde(default)]
+    pos: String,
+    #[serde(default)]
+    senses: Vec<WiktionarySense>,
+}
+
+#[derive(Deserialize)]
+struct WiktionarySense {
+    #[serde(default)]
+    glosses: Vec<String>,
+}
+
+/// The dictionary given with --dictionary, or the one in rustle's data folder if there is one.
+pub fn find(path: Option<&str>) -> Result<PathBuf, String> {
+    if let Some(path) = path {
+        return Ok(PathBuf::from(path));
+    }
+    let default = dirs::data_dir()
+        .map(|dir| dir.join("rustle").join("dictionary"))
+        .ok_or("Can't find the data folder, use --dictionary to say where the dictionary is")?;
+    if !default.exists() {
+        return Err(format!(
+            "No dictionary found, put one in {} or use --dictionary to say where it is",
+            default.display()
+        ));
+    }
+    Ok(default)
+}
+
+/// All the definitions of the word in the dictionary.
+pub fn define(path: &Path, word: &str) -> Result<Vec<Definition>, String> {
+    let word = word.to_lowercase();
+    if path.is_dir() {
+        let mut definitions = Vec::new();
+        for file in WORDNET_FILES.iter().map(|f| path.join(f)).filter(|f| f.exists()) {
+            definitions.extend(search(&file, &word, wordnet_line)?);
+        }
+        return Ok(definitions);
+    }
+
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    if WORDNET_FILES.contains(&name) {
+        search(path, &word, wordnet_line)
+    } else if extension == "jsonl" || extension == "json" {
+        search(path, &word, wiktionary_line)
+    } else {
+        search(path, &word, tsv_line)
+    }
+}
+
+fn search(
+    path: &Path,
+    word: &str,
+    parse: fn(&str, &str) -> Vec<Definition>,
+) -> Result<Vec<Definition>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Can't open {}: {}", path.display(), e))?;
+    let mut definitions = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+        // Much quicker than parsing every line
+        if line.to_lowercase().contains(word) {
+            definitions.extend(parse(&line, word));
+        }
+    }
+    Ok(definitions)
+}
+
+/// `offset lex_filenum ss_type w_cnt word lex_id [word lex_id...] ... | gloss`
+fn wordnet_line(line: &str, word: &str) -> Vec<Definition> {
+    // The licence at the top of the files is indented
+    if line.starts_with(' ') {
+        return Vec::new();
+    }
+    let Some((data, gloss)) = line.split_once(" | ") else {
+        return Vec::new();
+    };
+    let fields: Vec<&str> = data.split(' ').collect();
+    if fields.len() < 4 {
+        return Vec::new();
+    }
+    let count = usize::from_str_radix(fields[3], 16).unwrap_or(0);
+    let matches = (0..count)
+        .filter_map(|n| fields.get(4 + n * 2))
+        // Adjectives can have a marker like "(a)" after them
+        .map(|w| w.split('(').next().unwrap().to_lowercase())
+        .any(|w| w == word);
+    if !matches {
+        return Vec::new();
+    }
+    let part_of_speech = match fields[2] {
+        "n" => "noun",
+        "v" => "verb",
+        "a" | "s" => "adjective",
+        "r" => "adverb",
+        _ => "",
+    };
+    // The examples come after the definition, in quotes
+    let text = gloss.split("; \"").next().unwrap().trim();
+    vec![Definition {
+        part_of_speech: part_of_speech.to_string(),
+        text: text.to_string(),
+    }]
+}
+
+fn wiktionary_line(line: &str, word: &str) -> Vec<Definition> {
+    match serde_json::from_str::<WiktionaryEntry>(line) {
+        Ok(entry) if entry.word.to_lowercase() == word => entry
+            .senses
+            .into_iter()
+            .filter_map(|s| s.glosses.into_iter().next())
+            .map(|text| Definition {
+                part_of_speech: entry.pos.clone(),
+                text,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `word<tab>part of speech<tab>definition`
+fn tsv_line(line: &str, word: &str) -> Vec<Definition> {
+    let mut fields = line.trim_end_matches('\r').splitn(3, '\t');
+    match (fields.next(), fields.next(), fields.next()) {
+        (Some(w), Some(pos), Some(text)) if w.trim().to_lowercase() == word => vec![Definition {
+            part_of_speech: pos.trim().to_string(),
+            text: text.trim().to_string(),
+        }],
+        _ => Vec::new(),
+    }
+}
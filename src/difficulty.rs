@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::lists::Entry;
+
+// --- Difficulty levels, picking answers by how common they are ---
+//
+// A list can say how hard every word is in its difficulty column, with the names of the levels.
+// Otherwise the words are sorted by frequency, from the list or from a frequency table, and split
+// into four equal tiers: the most common quarter is easy, the rarest quarter is expert. Words
+// without a frequency aren't in any tier.
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Expert,
+}
+
+const LEVELS: [Difficulty; 4] = [
+    Difficulty::Easy,
+    Difficulty::Normal,
+    Difficulty::Hard,
+    Difficulty::Expert,
+];
+
+impl Difficulty {
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+
+    /// Easy words get a couple of extra guesses, rare ones fewer.
+    pub fn default_guesses(self) -> u32 {
+        match self {
+            Difficulty::Easy => 8,
+            Difficulty::Normal => 6,
+            Difficulty::Hard => 5,
+            Difficulty::Expert => 4,
+        }
+    }
+
+    /// Keeps the words of this difficulty. `frequencies` are used for words the list has no
+    /// frequency for.
+    pub fn select(self, entries: Vec<Entry>, frequencies: Option<&HashMap<String, u64>>) -> Result<Vec<Entry>, String> {
+        let named = |e: &Entry| {
+            e.difficulty
+                .as_ref()
+                .is_some_and(|d| LEVELS.iter().any(|l| d.eq_ignore_ascii_case(l.name())))
+        };
+        if entries.iter().any(named) {
+            return Ok(entries
+                .into_iter()
+                .filter(|e| e.difficulty.as_ref().is_some_and(|d| d.eq_ignore_ascii_case(self.name())))
+                .collect());
+        }
+
+        let frequency = |e: &Entry| e.frequency.or_else(|| frequencies?.get(&e.word.to_lowercase()).copied());
+        // Words nobody counted can't be put in a tier, so they're left out
+        let mut sorted: Vec<(u64, Entry)> = entries.into_iter().filter_map(|e| Some((frequency(&e)?, e))).collect();
+        if sorted.is_empty() {
+            return Err(String::from(
+                "Difficulty levels need to know how common the words are. Use a list with a frequency \
+                 or difficulty column, or a frequency table with --frequencies",
+            ));
+        }
+        sorted.sort_by_key(|(frequency, _)| std::cmp::Reverse(*frequency));
+        let total = sorted.len();
+        let tier = LEVELS.iter().position(|&l| l == self).unwrap();
+        Ok(sorted
+            .into_iter()
+            .enumerate()
+            .filter(|(n, _)| n * LEVELS.len() / total == tier)
+            .map(|(_, (_, e))| e)
+            .collect())
+    }
+}
+
+/// Reads a frequency table: a word and how often it's used on every line, separated by spaces,
+/// a tab or a comma. Lines that aren't like that, like a header, are skipped.
+pub fn load_frequencies(path: &str) -> Result<HashMap<String, u64>, String> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+    let mut frequencies = HashMap::new();
+    for line in raw.lines() {
+        let mut fields = line.split(|c: char| c.is_whitespace() || c == ',').filter(|f| !f.is_empty());
+        if let (Some(word), Some(Ok(count))) = (fields.next(), fields.next().map(str::parse::<u64>)) {
+            *frequencies.entry(word.to_lowercase()).or_default() += count;
+        }
+    }
+    Ok(frequencies)
+}
+
+/// The frequency table given with --frequencies, or the one in rustle's data folder if there is one.
+pub fn find_frequencies(path: Option<&str>) -> Result<Option<HashMap<String, u64>>, String> {
+    match path {
+        Some(path) => load_frequencies(path).map(Some),
+        None => match dirs::data_dir().map(|dir| dir.join("rustle").join("frequencies.txt")) {
+            Some(default) if default.exists() => load_frequencies(&default.to_string_lossy()).map(Some),
+            _ => Ok(None),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_counted_words_are_tiered() {
+        let mut entries: Vec<Entry> = ["apple", "bread", "crane", "daisy"]
+            .iter()
+            .zip([40, 30, 20, 10])
+            .map(|(word, frequency)| Entry {
+                frequency: Some(frequency),
+                ..Entry::new(word)
+            })
+            .collect();
+        entries.insert(0, Entry::new("aaaaa"));
+        let words = |difficulty: Difficulty| -> Vec<String> {
+            let selected = difficulty.select(entries.clone(), None).unwrap();
+            selected.into_iter().map(|e| e.word).collect()
+        };
+        assert_eq!(words(Difficulty::Easy), ["apple"]);
+        assert_eq!(words(Difficulty::Expert), ["daisy"]);
+        assert!(Difficulty::Easy.select(vec![Entry::new("aaaaa")], None).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::game::Turn;
+use crate::scoring::{is_consistent, parse_pattern};
+
+// --- Narrowing a word list down to the words that fit what's known ---
+//
+// What's known can be feedback on earlier guesses, or a pattern like "s.[^ao]e." with letters
+// that have to be in the word somewhere, or can't be.
+
+/// One position of a pattern.
+enum Slot {
+    Any,
+    Letter(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl Slot {
+    fn allows(&self, c: char) -> bool {
+        match self {
+            Slot::Any => true,
+            Slot::Letter(letter) => c == *letter,
+            Slot::OneOf(letters) => letters.contains(&c),
+            Slot::NoneOf(letters) => !letters.contains(&c),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Constraints {
+    pub history: Vec<Turn>,
+    positions: Option<Vec<Slot>>,
+    include: Vec<char>,
+    exclude: Vec<char>,
+}
+
+impl Constraints {
+    /// Guesses followed by their feedback pattern, like: crane ..Y.G slate G.Y..
+    pub fn from_turns(turns: &[String]) -> Result<Constraints, String> {
+        if !turns.len().is_multiple_of(2) {
+            return Err(String::from("Every guess needs a feedback pattern after it"));
+        }
+        let mut history = Vec::new();
+        for pair in turns.chunks(2) {
+            let guess = pair[0].to_lowercase();
+            let feedback = parse_pattern(&pair[1])?;
+            if feedback.len() != guess.chars().count() {
+                return Err(format!("The pattern \"{}\" doesn't fit the guess \"{}\"", pair[1], guess));
+            }
+            history.push(Turn { guess, feedback });
+        }
+        if history.windows(2).any(|t| t[0].guess.chars().count() != t[1].guess.chars().count()) {
+            return Err(String::from("All the guesses need the same amount of letters"));
+        }
+        Ok(Constraints {
+            history,
+            ..Default::default()
+        })
+    }
+
+    /// A pattern with a letter or `.` for every position. `[abc]` is any of those letters, and
+    /// `[^abc]` any letter but those.
+    pub fn with_pattern(mut self, pattern: &str) -> Result<Constraints, String> {
+        let mut positions = Vec::new();
+        let mut chars = pattern.to_lowercase().chars().collect::<Vec<_>>().into_iter();
+        while let Some(c) = chars.next() {
+            let slot = match c {
+                '.' | '_' | '?' => Slot::Any,
+                '[' => {
+                    let mut letters = Vec::new();
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(c) => letters.push(c),
+                            None => return Err(format!("The pattern \"{}\" has a '[' without a ']'", pattern)),
+                        }
+                    }
+                    let none_of = letters.first() == Some(&'^');
+                    if none_of {
+                        letters.remove(0);
+                    }
+                    if letters.is_empty() {
+                        return Err(format!("The pattern \"{}\" has brackets without any letters in them", pattern));
+                    }
+                    if none_of {
+                        Slot::NoneOf(letters)
+                    } else {
+                        Slot::OneOf(letters)
+                    }
+                }
+                ']' | '^' => return Err(format!("The pattern \"{}\" has a stray '{}'", pattern, c)),
+                c => Slot::Letter(c),
+            };
+            positions.push(slot);
+        }
+        if let Some(length) = self.length() {
+            if length != positions.len() {
+                return Err(format!("The pattern \"{}\" doesn't have {} letters like the guesses", pattern, length));
+            }
+        }
+        self.positions = Some(positions);
+        Ok(self)
+    }
+
+    /// Letters that have to be somewhere in the word, and letters that can't be.
+    pub fn with_letters(mut self, include: &str, exclude: &str) -> Constraints {
+        self.include = include.to_lowercase().chars().collect();
+        self.exclude = exclude.to_lowercase().chars().collect();
+        self
+    }
+
+    /// How long the words have to be, if the guesses or the pattern say so.
+    pub fn length(&self) -> Option<usize> {
+        match (&self.positions, self.history.first()) {
+            (Some(positions), _) => Some(positions.len()),
+            (None, Some(turn)) => Some(turn.feedback.len()),
+            (None, None) => None,
+        }
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        if let Some(positions) = &self.positions {
+            if word.chars().count() != positions.len()
+                || !word.chars().zip(positions).all(|(c, slot)| slot.allows(c))
+            {
+                return false;
+            }
+        }
+        self.include.iter().all(|&c| word.contains(c))
+            && !self.exclude.iter().any(|&c| word.contains(c))
+            && self.history.iter().all(|t| is_consistent(word, &t.guess, &t.feedback))
+    }
+
+    /// The words that fit everything.
+    pub fn filter(&self, words: &[String]) -> Vec<String> {
+        words.iter().filter(|w| self.matches(w)).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn patterns() {
+        let list = words(&["slate", "state", "skate", "plate", "crane"]);
+        let matches = |pattern: &str| Constraints::default().with_pattern(pattern).unwrap().filter(&list);
+        assert_eq!(matches("s.ate"), words(&["slate", "state", "skate"]));
+        assert_eq!(matches("S_A?E"), words(&["slate", "state", "skate"]));
+        assert_eq!(matches("s[lk]ate"), words(&["slate", "skate"]));
+        assert_eq!(matches("[^s]late"), words(&["plate"]));
+        // Too short for every word
+        assert!(matches("s.at").is_empty());
+    }
+
+    #[test]
+    fn broken_patterns() {
+        for pattern in ["s[ab", "s[]ate", "s[^]ate", "sl]te", "s^ate"] {
+            assert!(Constraints::default().with_pattern(pattern).is_err(), "{}", pattern);
+        }
+        let turns = Constraints::from_turns(&words(&["crane", "..Y.G"])).unwrap();
+        assert!(turns.with_pattern("s.ate.").is_err());
+    }
+
+    #[test]
+    fn letters_and_turns() {
+        let list = words(&["slate", "state", "skate", "plate", "crane"]);
+        let constraints = Constraints::default().with_letters("k", "");
+        assert_eq!(constraints.filter(&list), words(&["skate"]));
+        let constraints = Constraints::from_turns(&words(&["crane", "..G.G"])).unwrap().with_letters("", "p");
+        assert_eq!(constraints.filter(&list), words(&["slate", "state", "skate"]));
+    }
+}
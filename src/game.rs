@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::scoring::{score, Feedback};
+
+// --- The rules of a single game, without any input or output ---
+
+/// A guess that was made, and the feedback it got.
+#[derive(Clone)]
+pub struct Turn {
+    pub guess: String,
+    pub feedback: Vec<Feedback>,
+}
+
+#[derive(Clone)]
+pub struct Game {
+    pub word: String,
+    pub guesses: u32,
+    pub history: Vec<Turn>,
+    /// When the game started, and how long into the game every guess was made
+    pub started: SystemTime,
+    pub times: Vec<Duration>,
+    clock: Instant,
+    /// Lost to the clock, with guesses left
+    out_of_time: bool,
+}
+
+pub enum GuessError {
+    /// The guess doesn't have as many letters as the word. It doesn't count as a guess.
+    WrongLength { expected: usize, got: usize },
+    /// The game is already won or lost
+    Over,
+}
+
+impl std::fmt::Display for GuessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GuessError::WrongLength { expected, got } => write!(
+                f,
+                "The word has {} letters, but the guess had {}",
+                expected, got
+            ),
+            GuessError::Over => write!(f, "The game is over"),
+        }
+    }
+}
+
+impl Game {
+    pub fn new(word: &str, guesses: u32) -> Game {
+        Game {
+            word: word.to_string(),
+            guesses,
+            history: Vec::new(),
+            started: SystemTime::now(),
+            times: Vec::new(),
+            clock: Instant::now(),
+            out_of_time: false,
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.word.chars().count()
+    }
+
+    pub fn guesses_left(&self) -> u32 {
+        self.guesses.saturating_sub(self.history.len() as u32)
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.history.last().is_some_and(|t| t.guess == self.word)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.guesses_left() == 0 || self.out_of_time
+    }
+
+    pub fn is_out_of_time(&self) -> bool {
+        self.out_of_time
+    }
+
+    /// Ends the game as lost, when it's played against the clock and the time is up.
+    pub fn run_out_of_time(&mut self) {
+        self.out_of_time = true;
+    }
+
+    /// Scores the guess and adds it to the history.
+    pub fn guess(&mut self, input: &str) -> Result<&Turn, GuessError> {
+        if self.is_over() {
+            return Err(GuessError::Over);
+        }
+        let got = input.chars().count();
+        if got != self.length() {
+            return Err(GuessError::WrongLength {
+                expected: self.length(),
+                got,
+            });
+        }
+        self.history.push(Turn {
+            guess: input.to_string(),
+            feedback: score(&self.word, input),
+        });
+        self.times.push(self.clock.elapsed());
+        Ok(self.history.last().unwrap())
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::{stdout, Write};
+
+use colorful::Colorful;
+use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::{cursor, terminal, terminal::ClearType, ExecutableCommand};
+
+use crate::input::{self, Input};
+use crate::{finish_game, get_word_list, play, play_again, quit_game, time_limit, Args};
+
+// --- Hot-seat: two players on one computer, picking the word for each other ---
+pub fn play_hotseat(args: &Args, input: &mut Input) {
+    let words = get_word_list(args);
+    let names = [ask_name(1, input), ask_name(2, input)];
+    let mut score = [0u32; 2];
+    let mut setter = 0;
+
+    loop {
+        let guesser = 1 - setter;
+        println!(
+            "\n{}, pick a word for {}. Don't let them peek! 🙈",
+            names[setter].clone().bold(),
+            names[guesser]
+        );
+        let Some(word) = read_secret_word(&words, input) else {
+            println!("\nNo secret word, that's the end of the game. 👋");
+            break;
+        };
+        if !input.is_scripted() {
+            clear_screen();
+        }
+
+        println!("{}, your turn to guess!", names[guesser].clone().bold());
+        let game = play(&word, &args.guesses(), args, input, time_limit(args));
+        if !game.is_over() {
+            quit_game(args, &game);
+            break;
+        }
+        finish_game(args, &game);
+        if game.is_won() {
+            score[guesser] += 1;
+        } else {
+            score[setter] += 1;
+        }
+        println!(
+            "\nScore: {} {} - {} {}",
+            names[0], score[0], score[1], names[1]
+        );
+
+        if !play_again(args, input) {
+            break;
+        }
+        setter = guesser;
+    }
+}
+
+fn ask_name(player: u32, input: &mut Input) -> String {
+    if !input.is_scripted() {
+        print!("Name of player {} (press enter for \"Player {}\"): ", player, player);
+        stdout().flush().unwrap();
+    }
+    match input.read_line().as_deref() {
+        None | Some("") => format!("Player {}", player),
+        Some(name) => name.to_string(),
+    }
+}
+
+/// Reads the word without showing it, until it's one from the list. Scripts have the word on a
+/// line of their own. `None` when there is no more input.
+fn read_secret_word(words: &[String], input: &mut Input) -> Option<String> {
+    loop {
+        let word = if input.is_scripted() {
+            input.read_line()?.to_lowercase()
+        } else {
+            print!("Secret word: ");
+            stdout().flush().unwrap();
+            read_hidden().trim().to_lowercase()
+        };
+        if words.contains(&word) {
+            return Some(word);
+        }
+        if !words.iter().any(|w| w.chars().count() == word.chars().count()) {
+            println!("There are no words with {} letters in the list, try another one.", word.chars().count());
+        } else {
+            println!("That word isn't in the list, try another one.");
+        }
+    }
+}
+
+/// Reads a line from the terminal with echo turned off, showing a * for every character.
+fn read_hidden() -> String {
+    let mut input = String::new();
+    terminal::enable_raw_mode().unwrap();
+    loop {
+        if let Event::Key(KeyEvent { code, modifiers }) = read().unwrap() {
+            match code {
+                KeyCode::Enter => break,
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => input::quit(130),
+                KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => input::quit(0),
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    print!("*");
+                }
+                KeyCode::Backspace if !input.is_empty() => {
+                    input.pop();
+                    print!("\u{8} \u{8}");
+                }
+                _ => {}
+            }
+            stdout().flush().unwrap();
+        }
+    }
+    terminal::disable_raw_mode().unwrap();
+    println!();
+    input
+}
+
+/// Clears the screen and the scrollback, so the word can't be found by scrolling up.
+fn clear_screen() {
+    stdout().execute(terminal::Clear(ClearType::All)).unwrap();
+    stdout().execute(terminal::Clear(ClearType::Purge)).unwrap();
+    stdout().execute(cursor::MoveTo(0, 0)).unwrap();
+}
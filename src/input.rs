@@ -0,0 +1,166 @@
+use std::fs::File;
+use std::io::{stdin, stdout, BufRead, BufReader, IsTerminal, Write};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::{cursor, queue, terminal, terminal::ClearType, ExecutableCommand};
+
+use crate::game::Game;
+
+// --- Where the guesses come from: the player at the terminal, or a script ---
+//
+// A script is a file with one guess per line, or whatever is piped into stdin. Scripted games
+// don't move the cursor around, so the output is the same every time for the same word.
+//
+// Ctrl-C, and Ctrl-D in raw mode, go through `quit`, which puts the terminal back the way it
+// was and hands the game that was going on to the handler from `catch_interrupts`.
+//
+// Against the clock, the line is read a key at a time in raw mode, so the prompt with the time
+// left can be redrawn while the player types. Scripts are only checked against the clock after
+// every line.
+
+// How often the clock in the prompt is redrawn
+const TICK: Duration = Duration::from_millis(100);
+
+type QuitHandler = Box<dyn Fn(&Game) + Send>;
+
+// The game being played, kept up to date by the game loops
+static CURRENT_GAME: Mutex<Option<Game>> = Mutex::new(None);
+static ON_QUIT: Mutex<Option<QuitHandler>> = Mutex::new(None);
+
+/// What came of reading a line against the clock.
+pub enum Timed {
+    Line(String),
+    TimeUp,
+    End,
+}
+
+pub struct Input {
+    file: Option<BufReader<File>>,
+    scripted: bool,
+}
+
+impl Input {
+    /// Reads from the script file if there is one, otherwise from stdin. Stdin counts as a script
+    /// when it isn't a terminal.
+    pub fn open(script: Option<&str>) -> Result<Input, String> {
+        let file = match script {
+            Some(path) if path != "-" => {
+                Some(BufReader::new(File::open(path).map_err(|e| format!("Can't open {}: {}", path, e))?))
+            }
+            _ => None,
+        };
+        Ok(Input {
+            scripted: script.is_some() || !stdin().is_terminal(),
+            file,
+        })
+    }
+
+    pub fn is_scripted(&self) -> bool {
+        self.scripted
+    }
+
+    /// The next line, trimmed, or `None` when there is no more input. Scripts skip empty lines.
+    pub fn read_line(&mut self) -> Option<String> {
+        loop {
+            let mut line = String::new();
+            let read = match &mut self.file {
+                Some(file) => file.read_line(&mut line),
+                None => stdin().read_line(&mut line),
+            };
+            match read {
+                Ok(0) | Err(_) => return None,
+                Ok(_) if self.scripted && line.trim().is_empty() => continue,
+                Ok(_) => return Some(line.trim().to_string()),
+            }
+        }
+    }
+
+    /// Like `read_line`, but gives up at the deadline. The prompt gets the time that's left, and
+    /// is shown again every tick. When the time is up, the prompt is cleared away.
+    pub fn read_line_before(&mut self, deadline: Instant, prompt: impl Fn(Duration) -> String) -> Timed {
+        if self.scripted {
+            return match self.read_line() {
+                _ if Instant::now() >= deadline => Timed::TimeUp,
+                Some(line) => Timed::Line(line),
+                None => Timed::End,
+            };
+        }
+
+        let mut line = String::new();
+        terminal::enable_raw_mode().unwrap();
+        let read = loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break Timed::TimeUp;
+            }
+            let mut out = stdout();
+            queue!(out, cursor::MoveToColumn(0), terminal::Clear(ClearType::CurrentLine)).unwrap();
+            print!("{}{}", prompt(left), line);
+            out.flush().unwrap();
+
+            if !poll(TICK.min(left)).unwrap() {
+                continue;
+            }
+            if let Event::Key(KeyEvent { code, modifiers }) = read().unwrap() {
+                match code {
+                    KeyCode::Enter => break Timed::Line(line.trim().to_string()),
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => quit(130),
+                    KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) && line.is_empty() => {
+                        break Timed::End
+                    }
+                    KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => line.push(c),
+                    KeyCode::Backspace => {
+                        line.pop();
+                    }
+                    _ => {}
+                }
+            }
+        };
+        terminal::disable_raw_mode().unwrap();
+        match read {
+            Timed::Line(_) => println!(),
+            Timed::TimeUp => {
+                let mut out = stdout();
+                queue!(out, cursor::MoveToColumn(0), terminal::Clear(ClearType::CurrentLine)).unwrap();
+                out.flush().unwrap();
+            }
+            Timed::End => {}
+        }
+        read
+    }
+}
+
+/// Makes Ctrl-C quit through `quit`. The handler gets the game that was interrupted, if any.
+pub fn catch_interrupts(handler: impl Fn(&Game) + Send + 'static) {
+    *ON_QUIT.lock().unwrap() = Some(Box::new(handler));
+    // Only fails if a handler was set already
+    let _ = ctrlc::set_handler(|| quit(130));
+}
+
+/// Remembers the game, for when it gets interrupted. `None` once it's over.
+pub fn set_current_game(game: Option<&Game>) {
+    *CURRENT_GAME.lock().unwrap() = game.cloned();
+}
+
+/// Leaves raw mode, shows the cursor and wraps up the current game, then exits with the code.
+pub fn quit(code: i32) -> ! {
+    restore_terminal();
+    if stdout().is_terminal() {
+        // Off the line with the ^C
+        println!();
+    }
+    if let Some(game) = CURRENT_GAME.lock().unwrap().take() {
+        if let Some(handler) = ON_QUIT.lock().unwrap().as_ref() {
+            handler(&game);
+        }
+    }
+    std::process::exit(code);
+}
+
+pub fn restore_terminal() {
+    let _ = terminal::disable_raw_mode();
+    let _ = stdout().execute(cursor::Show);
+    let _ = stdout().flush();
+}
@@ -0,0 +1,451 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use colorful::{Color, Colorful};
+use serde::Deserialize;
+
+// --- Tools for looking after word list files ---
+//
+// Lists are one word on each line, like the ones in word_lists/. Files are written back with the
+// same line endings they were read with, so CRLF lists stay CRLF.
+//
+// A list can start with a header that says what's in it, one `#!rustle key: value` line for
+// each thing it knows:
+//
+//   #!rustle name: Five letter English words
+//   #!rustle language: en
+//   #!rustle alphabet: abcdefghijklmnopqrstuvwxyz
+//   #!rustle length: 5
+//   #!rustle source: https://example.com/words
+//   #!rustle licence: CC0
+//
+// Header lines always end with a newline, whatever the separator of the words is.
+//
+// Lists can also tell more about every word, as .csv, .tsv or .json files. CSV and TSV files have
+// a row with the column names first, JSON files are an array of objects. The columns are:
+//
+//   word, frequency, difficulty, part_of_speech (or pos), definition
+//
+// Only the word is needed, the rest can be left empty. The tools below work on plain lists.
+
+pub const HEADER_PREFIX: &str = "#!rustle";
+
+#[derive(Clone, Debug, Default)]
+pub struct Header {
+    pub name: Option<String>,
+    pub language: Option<String>,
+    pub alphabet: Option<String>,
+    pub length: Option<usize>,
+    pub source: Option<String>,
+    pub licence: Option<String>,
+    /// Lines of the header that didn't make sense, for `rustle lists validate`
+    pub problems: Vec<String>,
+}
+
+impl Header {
+    fn set(&mut self, line: &str) {
+        let Some((key, value)) = line.split_once(':') else {
+            return self.problems.push(format!("\"{}\" isn't like \"key: value\"", line));
+        };
+        let value = value.trim().to_string();
+        match key.trim().to_lowercase().as_str() {
+            "name" => self.name = Some(value),
+            "language" => self.language = Some(value),
+            "alphabet" => self.alphabet = Some(value.to_lowercase()),
+            "length" => match value.parse() {
+                Ok(length) => self.length = Some(length),
+                Err(_) => self.problems.push(format!("The length \"{}\" isn't a number", value)),
+            },
+            "source" => self.source = Some(value),
+            "licence" | "license" => self.licence = Some(value),
+            other => self.problems.push(format!("Unknown header key \"{}\"", other)),
+        }
+    }
+
+    /// Whether every letter of the word is in the alphabet, if the list has one.
+    pub fn allows(&self, word: &str) -> bool {
+        match &self.alphabet {
+            Some(alphabet) => word.chars().all(|c| alphabet.contains(c)),
+            None => word.chars().all(char::is_alphabetic),
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let fields = [
+            ("name", self.name.clone()),
+            ("language", self.language.clone()),
+            ("alphabet", self.alphabet.clone()),
+            ("length", self.length.map(|l| l.to_string())),
+            ("source", self.source.clone()),
+            ("licence", self.licence.clone()),
+        ];
+        fields
+            .into_iter()
+            .filter_map(|(key, value)| Some(format!("{} {}: {}", HEADER_PREFIX, key, value?)))
+            .collect()
+    }
+}
+
+/// Splits the header off the top of a list file. Files without one are all words.
+pub fn split_header(raw: &str) -> (Header, &str) {
+    let mut header = Header::default();
+    let mut rest = raw;
+    while let Some(line) = rest.strip_prefix(HEADER_PREFIX) {
+        let (line, after) = line.split_once('\n').unwrap_or((line, ""));
+        header.set(line.trim());
+        rest = after;
+    }
+    (header, rest)
+}
+
+/// A word from a list, with whatever the list knows about it.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Entry {
+    pub word: String,
+    /// How common the word is, as a count. Higher is more common.
+    pub frequency: Option<u64>,
+    pub difficulty: Option<String>,
+    #[serde(alias = "pos")]
+    pub part_of_speech: Option<String>,
+    pub definition: Option<String>,
+}
+
+impl Entry {
+    pub fn new(word: &str) -> Entry {
+        Entry {
+            word: word.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonEntry {
+    Full(Entry),
+    Word(String),
+}
+
+/// Reads a list file of any format. Plain lists are split with the separator.
+pub fn read_entries(path: &str, separator: &str) -> Result<(Header, Vec<Entry>), String> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+    let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("");
+    if extension.eq_ignore_ascii_case("json") {
+        let entries: Vec<JsonEntry> =
+            serde_json::from_str(&raw).map_err(|e| format!("{} isn't a valid list: {}", path, e))?;
+        let entries = entries
+            .into_iter()
+            .map(|e| match e {
+                JsonEntry::Full(entry) => entry,
+                JsonEntry::Word(word) => Entry::new(&word),
+            })
+            .filter(|e| !e.word.trim().is_empty())
+            .map(|e| Entry {
+                word: e.word.trim().to_string(),
+                ..e
+            })
+            .collect();
+        return Ok((Header::default(), entries));
+    }
+
+    let (header, body) = split_header(&raw);
+    let entries = match extension.to_ascii_lowercase().as_str() {
+        "csv" => read_table(path, body, ',')?,
+        "tsv" => read_table(path, body, '\t')?,
+        _ => body
+            .split(separator)
+            .map(|w| w.trim())
+            .filter(|w| !w.is_empty())
+            .map(Entry::new)
+            .collect(),
+    };
+    Ok((header, entries))
+}
+
+/// Reads CSV or TSV rows into entries, using the first row for the column names.
+fn read_table(path: &str, body: &str, delimiter: char) -> Result<Vec<Entry>, String> {
+    let mut rows = body.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    let columns: Vec<String> = match rows.next() {
+        Some((_, line)) => split_row(line, delimiter).iter().map(|c| c.to_lowercase()).collect(),
+        None => return Ok(Vec::new()),
+    };
+    let column = |names: &[&str]| columns.iter().position(|c| names.contains(&c.as_str()));
+    let word = column(&["word"]).ok_or_else(|| format!("{} has no \"word\" column", path))?;
+    let frequency = column(&["frequency", "freq", "count"]);
+    let difficulty = column(&["difficulty", "tier"]);
+    let part_of_speech = column(&["part_of_speech", "pos"]);
+    let definition = column(&["definition", "meaning"]);
+
+    let mut entries = Vec::new();
+    for (n, line) in rows {
+        let cells = split_row(line, delimiter);
+        let cell = |i: Option<usize>| {
+            i.and_then(|i| cells.get(i))
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+        };
+        let Some(word) = cell(Some(word)) else {
+            continue;
+        };
+        let frequency = match cell(frequency) {
+            Some(f) => Some(f.parse().map_err(|_| {
+                format!("{}:{} the frequency \"{}\" isn't a whole number", path, n + 1, f)
+            })?),
+            None => None,
+        };
+        entries.push(Entry {
+            word,
+            frequency,
+            difficulty: cell(difficulty),
+            part_of_speech: cell(part_of_speech),
+            definition: cell(definition),
+        });
+    }
+    Ok(entries)
+}
+
+/// Splits a row into cells. Cells can be in double quotes, to have the delimiter in them, with
+/// "" for a quote.
+fn split_row(line: &str, delimiter: char) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cells.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells
+}
+
+struct ListFile {
+    header: Header,
+    /// How many lines the header takes, so line numbers match the file
+    header_lines: usize,
+    lines: Vec<String>,
+    crlf: bool,
+}
+
+/// Reads a plain list. Tables would be rewritten as if every row was a word, so they're refused.
+fn read(path: &str) -> Result<ListFile, String> {
+    let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("");
+    if ["csv", "tsv", "json"].iter().any(|e| extension.eq_ignore_ascii_case(e)) {
+        return Err(format!("{} isn't a plain list, the list tools only work on one word per line", path));
+    }
+    let raw = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+    let crlf = raw.contains("\r\n");
+    let (header, body) = split_header(&raw);
+    let header_lines = raw[..raw.len() - body.len()].matches('\n').count();
+    let mut lines: Vec<String> = body.split('\n').map(|l| l.to_string()).collect();
+    // A newline at the end of the file doesn't make an empty line
+    if lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    Ok(ListFile {
+        header,
+        header_lines,
+        lines,
+        crlf,
+    })
+}
+
+/// The words as they're written, so the tools don't change the case of what they write back.
+fn words(file: &ListFile) -> Vec<String> {
+    file.lines
+        .iter()
+        .map(|l| l.trim().to_string())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn write(path: &Path, header: &Header, words: &[String], crlf: bool) -> Result<(), String> {
+    let newline = if crlf { "\r\n" } else { "\n" };
+    let mut text = String::new();
+    for line in header.lines() {
+        text += &line;
+        text += newline;
+    }
+    text += &words.join(newline);
+    text += newline;
+    fs::write(path, text).map_err(|e| format!("Can't write {}: {}", path.display(), e))
+}
+
+/// Keeps the first of every word, in the order they came. Words that only differ in case are
+/// the same word.
+fn unique(words: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    words.into_iter().filter(|w| seen.insert(w.to_lowercase())).collect()
+}
+
+/// The word length a file is meant to have, from a name like `5.txt`.
+fn length_from_name(path: &str) -> Option<usize> {
+    Path::new(path).file_stem()?.to_str()?.parse().ok()
+}
+
+/// Reports everything that looks off in the list. It's an error if anything does, so it can
+/// be used in scripts.
+pub fn validate(path: &str, length: Option<usize>) -> Result<(), String> {
+    let file = read(path)?;
+    let length = length.or(file.header.length).or_else(|| length_from_name(path));
+    let mut problems = 0;
+    let mut report = |line: usize, text: String| {
+        problems += 1;
+        println!("{} {}", format!("{}:{}", path, line + 1).color(Color::LightGray), text);
+    };
+
+    for problem in &file.header.problems {
+        report(0, format!("header: {}", problem));
+    }
+    let mut first_seen: HashMap<String, usize> = HashMap::new();
+    for (n, line) in file.lines.iter().enumerate() {
+        let n = n + file.header_lines;
+        let word = line.trim();
+        if word.is_empty() {
+            report(n, String::from("empty line"));
+            continue;
+        }
+        if word.starts_with(HEADER_PREFIX) {
+            report(n, String::from("header lines have to be at the top of the file"));
+            continue;
+        }
+        if !file.header.allows(&word.to_lowercase()) {
+            let c = word.to_lowercase().chars().find(|&c| !file.header.allows(&c.to_string())).unwrap();
+            report(n, format!("\"{}\" has a character that isn't in the alphabet: {:?}", word, c));
+        }
+        if let Some(length) = length {
+            if word.chars().count() != length {
+                report(n, format!("\"{}\" doesn't have {} letters", word, length));
+            }
+        }
+        match first_seen.get(&word.to_lowercase()) {
+            Some(first) => report(n, format!("\"{}\" is already on line {}", word, first + 1)),
+            None => {
+                first_seen.insert(word.to_lowercase(), n);
+            }
+        }
+    }
+    if file.crlf {
+        println!("{} uses CRLF line endings, rustle reads those fine but other tools may not.", path);
+    }
+
+    match problems {
+        0 => {
+            println!("{} looks good, {} words. ✅", path, first_seen.len());
+            Ok(())
+        }
+        1 => Err(String::from("Found 1 problem")),
+        n => Err(format!("Found {} problems", n)),
+    }
+}
+
+/// Removes repeated words and empty lines, in place or into another file.
+pub fn dedupe(path: &str, output: Option<&str>) -> Result<(), String> {
+    let file = read(path)?;
+    let before = file.lines.len();
+    let words = unique(words(&file));
+    let output = output.unwrap_or(path);
+    write(Path::new(output), &file.header, &words, file.crlf)?;
+    println!("Wrote {} words to {}, {} lines removed.", words.len(), output, before - words.len());
+    Ok(())
+}
+
+/// Writes every word into a file named after its length, like `5.txt`, in the folder.
+pub fn split_by_length(path: &str, dir: &str) -> Result<(), String> {
+    let file = read(path)?;
+    let mut by_length: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for word in unique(words(&file)) {
+        by_length.entry(word.chars().count()).or_default().push(word);
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Can't create {}: {}", dir, e))?;
+    for (length, words) in &by_length {
+        let output = Path::new(dir).join(format!("{}.txt", length));
+        let header = Header {
+            length: Some(*length),
+            ..file.header.clone()
+        };
+        write(&output, &header, words, file.crlf)?;
+        println!("{:>8} words in {}", words.len(), output.display());
+    }
+    Ok(())
+}
+
+/// Puts the lists together into one, sorted and without repeats.
+pub fn merge(paths: &[String], output: &str) -> Result<(), String> {
+    let mut all = Vec::new();
+    let mut crlf = false;
+    for path in paths {
+        let file = read(path)?;
+        crlf |= file.crlf;
+        all.extend(words(&file));
+    }
+    let mut all = unique(all);
+    all.sort_by_key(|w| w.to_lowercase());
+    // The lists may say different things about themselves, so the result has no header
+    write(Path::new(output), &Header::default(), &all, crlf)?;
+    println!("Wrote {} words from {} lists to {}.", all.len(), paths.len(), output);
+    Ok(())
+}
+
+pub fn stats(path: &str) -> Result<(), String> {
+    let file = read(path)?;
+    let words: Vec<String> = unique(words(&file)).iter().map(|w| w.to_lowercase()).collect();
+    if words.is_empty() {
+        return Err(format!("{} has no words", path));
+    }
+    println!("{} words in {}", words.len(), path);
+    for line in file.header.lines() {
+        println!(" {}", line.trim_start_matches(HEADER_PREFIX).trim().color(Color::LightGray));
+    }
+
+    let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+    for word in &words {
+        *lengths.entry(word.chars().count()).or_default() += 1;
+    }
+    if lengths.len() > 1 {
+        println!("\nLetters  Words");
+        for (length, count) in &lengths {
+            println!("{:>7}  {:>5}", length, count);
+        }
+    }
+
+    // How many words have each letter at least once
+    let mut letters: BTreeMap<char, usize> = BTreeMap::new();
+    for word in &words {
+        for c in word.chars().collect::<HashSet<_>>() {
+            *letters.entry(c).or_default() += 1;
+        }
+    }
+    let mut letters: Vec<(char, usize)> = letters.into_iter().collect();
+    letters.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    println!("\nLetter  In words");
+    for (c, count) in &letters {
+        println!("{:>6}  {:>7.1}%", c, *count as f64 * 100.0 / words.len() as f64);
+    }
+
+    // The most common letters in every position, for the most common length
+    let (&length, _) = lengths.iter().max_by_key(|(_, &count)| count).unwrap();
+    let same_length: Vec<&String> = words.iter().filter(|w| w.chars().count() == length).collect();
+    println!("\nMost common letters by position, in the {}-letter words", length);
+    for position in 0..length {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for word in &same_length {
+            *counts.entry(word.chars().nth(position).unwrap()).or_default() += 1;
+        }
+        let mut counts: Vec<(char, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let top: Vec<String> = counts
+            .iter()
+            .take(5)
+            .map(|(c, n)| format!("{} {:>4.1}%", c, *n as f64 * 100.0 / same_length.len() as f64))
+            .collect();
+        println!("{:>3}  {}", position + 1, top.join("   "));
+    }
+    Ok(())
+}
@@ -1,18 +1,65 @@
-use rand::Rng;
+mod analysis;
+mod bench;
+mod blocklist;
+mod challenge;
+mod dictionary;
+mod difficulty;
+mod filter;
+mod game;
+mod hotseat;
+mod input;
+mod lists;
+mod patterns;
+mod protocol;
+mod race;
+mod record;
+mod reverse;
+mod scoring;
+mod serve;
+mod solver;
+mod stats;
+
+use rand::rngs::StdRng;
+use rand::distributions::WeightedIndex;
+use rand::{Rng, SeedableRng};
 use std::fs;
 use std::io::{stdout, Write};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use rayon::prelude::*;
 use colorful::{Color, Colorful};
 use crossterm::{cursor, terminal, terminal::ClearType, ExecutableCommand};
 
+use scoring::{is_consistent, score, Feedback};
+use game::{Game, GuessError, Turn};
+use input::{Input, Timed};
+use solver::words_of_length;
+
 const COLOUR_BG_CORRECT: Color = Color::DarkGreen;
 const COLOUR_BG_MISPLACED: Color = Color::DarkOrange;
 const COLOUR_BG_WRONG: Color = Color::DarkGray;
 const COLOUR_FG: Color = Color::White;
+const DEMO_DELAY: Duration = Duration::from_millis(700);
+// Extra guesses for the next word in survival, on top of the ones that weren't used
+const SURVIVAL_BONUS: u32 = 4;
+
+/// How far a blitz or a survival run got, kept where Ctrl-C can find it to end the run.
+#[derive(Clone, Copy)]
+struct Session {
+    solved: u32,
+    /// Finished words, won or lost
+    played: u32,
+    started: Instant,
+    scripted: bool,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
 
 // --- Commandline arguments stuff ---
-#[derive(Parser, Debug)]
+#[derive(Parser, Clone, Debug)]
 #[clap(
     version,
     author = "Experimental Cyborg", 
@@ -20,185 +67,1279 @@ const COLOUR_FG: Color = Color::White;
     long_about = None
 )]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Specific word to use
-    #[clap(short, long, default_value = "", hide_default_value = true)]
+    #[clap(short, long, global = true, default_value = "", hide_default_value = true)]
     word: String,
 
     /// Path to a word list file
-    #[clap(short, long, default_value = "", hide_default_value = true)]
+    #[clap(short, long, global = true, default_value = "", hide_default_value = true)]
     list_file: String,
 
     /// Word separator for the word list
-    #[clap(short, long, default_value = "\n")]
+    #[clap(short, long, global = true, default_value = "\n")]
     separator: String,
 
-    /// Maximum amount of guesses
-    #[clap(short, long, default_value_t = 6)]
-    guesses: u32,
+    /// Maximum amount of guesses, 6 unless the difficulty has its own
+    #[clap(short, long, global = true)]
+    guesses: Option<u32>,
 
     /// Start a new game automatically after the game is over, don't ask
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     replay: bool,
 
     /// Exit after the game is over
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     noreplay: bool,
+
+    /// Seconds to find the word in, with a clock counting down. The game is lost at zero
+    #[clap(long, global = true, value_name = "SECONDS")]
+    time: Option<u64>,
+
+    /// Find as many words as you can before the --time runs out
+    #[clap(long, requires = "time")]
+    blitz: bool,
+
+    /// Keep going until you miss a word. Guesses you don't use carry over to the next word,
+    /// with a few more on top
+    #[clap(long, conflicts_with = "blitz")]
+    survival: bool,
+
+    /// Time every guess against your personal best, and keep the best times
+    #[clap(long, global = true)]
+    speedrun: bool,
+
+    /// Play a challenge code made with "rustle challenge create"
+    #[clap(long, default_value = "", hide_default_value = true, value_name = "CODE")]
+    challenge: String,
+
+    /// How to talk to the player: text for people, jsonl for scripts and bots
+    #[clap(long, arg_enum, default_value = "text")]
+    protocol: Protocol,
+
+    /// Show how many words in the list still match the feedback after every guess
+    #[clap(short, long, global = true)]
+    candidates: bool,
+
+    /// List the matching words too, when there are fewer than this many left
+    #[clap(long, global = true, default_value_t = 0, hide_default_value = true, value_name = "N")]
+    list_below: usize,
+
+    /// Show an analysis of every guess after the game is over
+    #[clap(short, long, global = true)]
+    analyze: bool,
+
+    /// Use precomputed feedback patterns for --analyze, cached on disk for each word list
+    #[clap(long, global = true)]
+    analyze_matrix: bool,
+
+    /// Save every game in this folder, to watch again with "rustle replay"
+    #[clap(long, global = true, value_name = "DIR")]
+    record: Option<String>,
+
+    /// Read the guesses from this file, one per line ("-" for stdin). Exits with 0 on a win,
+    /// 1 on a loss and 2 on errors
+    #[clap(long, global = true, value_name = "FILE")]
+    script: Option<String>,
+
+    /// Seed for picking the word, so the same seed gets the same word
+    #[clap(long, global = true, value_name = "N")]
+    seed: Option<u64>,
+
+    /// Pick common words more often, with a list file that has frequencies
+    #[clap(long, global = true)]
+    weighted: bool,
+
+    /// Only pick words with this difficulty, with a list file that has difficulties
+    #[clap(long, global = true, value_name = "DIFFICULTY")]
+    tier: Option<String>,
+
+    /// Pick answers by how common they are, from everyday words to rare ones
+    #[clap(long, global = true, arg_enum)]
+    difficulty: Option<difficulty::Difficulty>,
+
+    /// How common words are, a word and a count on every line. By default the
+    /// "rustle/frequencies.txt" file in the data folder
+    #[clap(long, global = true, value_name = "FILE")]
+    frequencies: Option<String>,
+
+    /// Never pick names, or words that aren't fit for work or kids. They can still be guessed
+    #[clap(long, global = true)]
+    family_friendly: bool,
+
+    /// Never pick the words in this file, one per line. Can be given more than once, and
+    /// "proper-nouns" or "offensive" are the lists that come with rustle
+    #[clap(long, global = true, value_name = "FILE")]
+    blocklist: Vec<String>,
+
+    /// Look up what the word means after every game
+    #[clap(long, global = true)]
+    define: bool,
+
+    /// A WordNet folder, Wiktionary JSON lines or word<tab>pos<tab>definition file to look words
+    /// up in. By default the "rustle/dictionary" file or folder in the data folder
+    #[clap(long, global = true, value_name = "PATH")]
+    dictionary: Option<String>,
+
+    /// Where to keep the personal bests. By default "rustle/stats.json" in the data folder
+    #[clap(long, global = true, value_name = "FILE")]
+    stats: Option<String>,
+}
+
+impl Args {
+    fn guesses(&self) -> u32 {
+        self.guesses
+            .unwrap_or_else(|| self.difficulty.map_or(6, |d| d.default_guesses()))
+    }
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Protocol {
+    Text,
+    Jsonl,
+}
+
+/// How `rustle score` shows the feedback
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ScoreFormat {
+    Colour,
+    Symbols,
+    Pattern,
 }
 
-fn get_word_from_internal() -> String {
-    let mut rng = rand::thread_rng();
-    String::from(INTERNAL_WORD_LIST[rng.gen_range(0..INTERNAL_LIST_LENGTH)])
+/// The order `rustle filter` lists the words in
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum FilterSort {
+    /// As they are in the word list
+    List,
+    Alphabetical,
+    /// Words with the most common letters first
+    Frequency,
+    /// Words that would tell the most about the answer first, like the entropy solver
+    Entropy,
 }
 
-fn get_word_from_list(path: &String, separator: &String) -> String {
-    let raw: String = fs::read_to_string(path).expect("Error opening file");
-    let lines: Vec<&str> = raw.as_str().split(separator).collect();
+#[derive(clap::Args, Clone, Debug)]
+struct SolverOptions {
+    /// Solver strategy: random, frequency, entropy, minimax or exec:<command>
+    #[clap(long, default_value = "frequency")]
+    strategy: String,
 
-    let mut rng = rand::thread_rng();
-    String::from(lines[rng.gen_range(0..lines.len())])
+    /// Use precomputed feedback patterns, cached on disk for each word list
+    #[clap(long)]
+    matrix: bool,
 }
 
-fn play(word: &String, guesses: &u32) {
+#[derive(Subcommand, Clone, Debug)]
+enum Command {
+    /// Let a solver play against every word in the list and report how it did
+    Bench {
+        #[clap(flatten)]
+        solver: SolverOptions,
+    },
+
+    /// Suggest the next guess, given the guesses so far and their feedback
+    Assist {
+        #[clap(flatten)]
+        solver: SolverOptions,
+
+        /// Word length, used when no guesses have been made yet
+        #[clap(long, default_value_t = 5)]
+        length: usize,
+
+        /// Guesses followed by their feedback pattern (G correct, Y misplaced, . wrong), like: crane ..Y.G
+        turns: Vec<String>,
+    },
+
+    /// List the words that fit the feedback so far, or a pattern
+    Filter {
+        /// Letters and dots for every position, like s..e. or s.[^ao]e. or [bc]rane
+        #[clap(long)]
+        pattern: Option<String>,
+
+        /// Letters that have to be in the word
+        #[clap(long, default_value = "", hide_default_value = true)]
+        include: String,
+
+        /// Letters that can't be in the word
+        #[clap(long, default_value = "", hide_default_value = true)]
+        exclude: String,
+
+        /// Word length, when there are no guesses or pattern to tell
+        #[clap(long)]
+        length: Option<usize>,
+
+        #[clap(long, arg_enum, default_value = "list")]
+        sort: FilterSort,
+
+        /// Guesses followed by their feedback pattern (G correct, Y misplaced, . wrong), like: crane ..Y.G
+        turns: Vec<String>,
+    },
+
+    /// Show the feedback guesses would get, without playing a game
+    Score {
+        /// colour: tiles like in the game, symbols: 🟩🟨⬛, pattern: the guess and GY..G
+        #[clap(long, arg_enum, default_value = "colour")]
+        format: ScoreFormat,
+
+        /// The answer to score against
+        answer: String,
+
+        /// The guesses to score
+        #[clap(required = true, value_name = "GUESS")]
+        played: Vec<String>,
+    },
+
+    /// Analyze a game that was played, guess by guess
+    Analyze {
+        /// Use precomputed feedback patterns, cached on disk for each word list
+        #[clap(long)]
+        matrix: bool,
+
+        /// The answer of the game
+        answer: String,
+
+        /// The guesses that were made, in order
+        #[clap(required = true, value_name = "GUESS")]
+        played: Vec<String>,
+    },
+
+    /// Think of a word and let the solver guess it
+    Reverse {
+        #[clap(flatten)]
+        solver: SolverOptions,
+
+        /// Length of the word you're thinking of
+        #[clap(long, default_value_t = 5)]
+        length: usize,
+    },
+
+    /// Two players on one computer, taking turns picking the word for each other
+    Hotseat,
+
+    /// Share a word with a friend as a code, without spoiling it
+    Challenge {
+        #[clap(subcommand)]
+        action: ChallengeAction,
+    },
+
+    /// Host a race on the network, first to guess the word wins
+    Host {
+        /// Port to listen on
+        #[clap(short, long, default_value_t = 7878)]
+        port: u16,
+
+        /// Your name, as the other players see it
+        #[clap(long, default_value = "host")]
+        name: String,
+    },
+
+    /// Join a race that someone is hosting
+    Join {
+        /// Address of the host, like 192.168.1.10:7878
+        address: String,
+
+        /// Your name, as the other players see it
+        #[clap(long, default_value = "player")]
+        name: String,
+    },
+
+    /// Serve games over HTTP, as a JSON API
+    Serve {
+        /// Port to listen on
+        #[clap(short, long, default_value_t = 8080)]
+        port: u16,
+
+        /// Folder with more word lists (.txt files) that games can pick, like word_lists
+        #[clap(long)]
+        lists_dir: Option<String>,
+    },
+
+    /// Watch a recorded game again
+    Replay {
+        /// The recording, made with --record
+        file: String,
+
+        /// How much faster than the original to play it back
+        #[clap(long, default_value_t = 1.0)]
+        speed: f64,
+
+        /// Wait for enter before every guess
+        #[clap(long)]
+        step: bool,
+    },
+
+    /// Watch a solver play a game
+    Demo {
+        #[clap(flatten)]
+        solver: SolverOptions,
+    },
+
+    /// Look up what words mean, in the word list or the dictionary
+    Define {
+        #[clap(required = true)]
+        words: Vec<String>,
+    },
+
+    /// Show your personal bests
+    Stats,
+
+    /// Check, clean up and combine word list files
+    Lists {
+        #[clap(subcommand)]
+        action: ListsAction,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+enum ChallengeAction {
+    /// Make a code for a word, using the word list and guesses given
+    Create {
+        /// The word to challenge your friend with
+        word: String,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+enum ListsAction {
+    /// Look for empty lines, repeated words, characters that aren't letters and wrong lengths
+    Validate {
+        file: String,
+
+        /// The length every word should have, if the file isn't named like 5.txt
+        #[clap(long)]
+        length: Option<usize>,
+    },
+
+    /// Remove repeated words and empty lines
+    Dedupe {
+        file: String,
+
+        /// Write the result here instead of changing the file
+        #[clap(short, long)]
+        output: Option<String>,
+    },
+
+    /// Write the words into a file for every length, named like 5.txt
+    SplitByLength {
+        file: String,
+
+        /// Folder for the files
+        #[clap(long, default_value = ".")]
+        out_dir: String,
+    },
+
+    /// Combine lists into one, sorted and without repeats
+    Merge {
+        #[clap(required = true, min_values = 2)]
+        files: Vec<String>,
+
+        #[clap(short, long)]
+        output: String,
+    },
+
+    /// Count the words, and how often every letter shows up, and where
+    Stats { file: String },
+}
+
+/// Random numbers for picking words, the same ones every time if there's a seed.
+fn word_rng(args: &Args) -> StdRng {
+    match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+fn pick_word(args: &Args, rng: &mut StdRng) -> String {
+    if !args.word.is_empty() {
+        debug_print("Word source: given word");
+        args.word.to_lowercase()
+    } else if !args.list_file.is_empty() {
+        debug_print("Word source: external word list");
+        let (header, entries) = read_word_list(&args.list_file, &args.separator);
+        if let Some(name) = header.name {
+            debug_print(format!("Word list: {}", name).as_str());
+        }
+        pick_entry(args, entries, &args.list_file, rng)
+    } else {
+        debug_print("Word source: internal word list");
+        let entries = INTERNAL_WORD_LIST.iter().map(|w| lists::Entry::new(w)).collect();
+        pick_entry(args, entries, "the internal word list", rng)
+    }
+}
+
+/// Picks one of the words that aren't blocked and fit the tier and difficulty, if there are any.
+fn pick_entry(args: &Args, mut entries: Vec<lists::Entry>, list: &str, rng: &mut StdRng) -> String {
+    let blocked = blocklist::load(args.family_friendly, &args.blocklist).unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        std::process::exit(2);
+    });
+    entries.retain(|e| !blocked.contains(&e.word.to_lowercase()));
+    if let Some(tier) = &args.tier {
+        entries.retain(|e| e.difficulty.as_ref().is_some_and(|d| d.eq_ignore_ascii_case(tier)));
+    }
+    if let Some(difficulty) = args.difficulty {
+        let selected = difficulty::find_frequencies(args.frequencies.as_deref())
+            .and_then(|frequencies| difficulty.select(entries, frequencies.as_ref()));
+        entries = selected.unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        });
+    }
+    if entries.is_empty() {
+        eprintln!("There are no words to pick from in {} with those settings", list);
+        std::process::exit(2);
+    }
+    if args.weighted {
+        // Words without a frequency count as rare
+        let weights = entries.iter().map(|e| e.frequency.unwrap_or(1).max(1));
+        let index = WeightedIndex::new(weights).unwrap();
+        return entries[rng.sample(index)].word.to_lowercase();
+    }
+    entries[rng.gen_range(0..entries.len())].word.to_lowercase()
+}
+
+fn load_word_list(path: &str, separator: &str) -> Vec<String> {
+    read_word_list(path, separator).1.into_iter().map(|e| e.word).collect()
+}
+
+/// The words of a list file, with what the list knows about them and what its header says.
+fn read_word_list(path: &str, separator: &str) -> (lists::Header, Vec<lists::Entry>) {
+    let (header, entries) = lists::read_entries(path, separator).unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        std::process::exit(2);
+    });
+    for problem in &header.problems {
+        debug_print(format!("{}: {}", path, problem).as_str());
+    }
+    (header, entries)
+}
+
+/// What the word list knows about the word, if it's a list with more than just words.
+fn word_entry(args: &Args, word: &str) -> Option<lists::Entry> {
+    if args.list_file.is_empty() {
+        return None;
+    }
+    read_word_list(&args.list_file, &args.separator).1.into_iter().find(|e| e.word.to_lowercase() == word.to_lowercase())
+}
+
+fn print_definition(word: &str, part_of_speech: &str, definition: &str) {
+    match part_of_speech {
+        "" => println!(" {}: {}", word.bold(), definition),
+        pos => println!(" {} ({}): {}", word.bold(), pos, definition),
+    }
+}
+
+/// Shows what the word means, from the word list if it says, or from the dictionary with --define.
+fn show_definition(args: &Args, word: &str) {
+    let message = match define_word(args, word, args.define) {
+        Ok(false) if args.define => format!("\"{}\" isn't in the dictionary. 📖", word),
+        Ok(_) => return,
+        Err(message) => message,
+    };
+    println!(" {}", message.color(Color::LightGray));
+}
+
+/// Prints the definitions of the word, and returns whether there were any. The dictionary is
+/// only used if `lookup` is set.
+fn define_word(args: &Args, word: &str, lookup: bool) -> Result<bool, String> {
+    if let Some(entry) = word_entry(args, word) {
+        if let Some(definition) = &entry.definition {
+            print_definition(word, entry.part_of_speech.as_deref().unwrap_or(""), definition);
+            return Ok(true);
+        }
+    }
+    if !lookup {
+        return Ok(false);
+    }
+    let path = dictionary::find(args.dictionary.as_deref())?;
+    let definitions = dictionary::define(&path, word)?;
+    for definition in definitions.iter().take(dictionary::MAX_DEFINITIONS) {
+        print_definition(word, &definition.part_of_speech, &definition.text);
+    }
+    Ok(!definitions.is_empty())
+}
+
+/// The words to work with: the given word list file, or the internal one.
+fn get_word_list(args: &Args) -> Vec<String> {
+    if !args.list_file.is_empty() {
+        load_word_list(&args.list_file, &args.separator)
+    } else {
+        INTERNAL_WORD_LIST.iter().map(|w| w.to_string()).collect()
+    }
+}
+
+fn format_feedback(guess: &str, feedback: &[Feedback]) -> String {
+    let mut tiles = String::new();
+    for (letter, result) in guess.chars().zip(feedback) {
+        let bg_color = match result {
+            Feedback::Correct => COLOUR_BG_CORRECT,
+            Feedback::Misplaced => COLOUR_BG_MISPLACED,
+            Feedback::Wrong => COLOUR_BG_WRONG,
+        };
+        tiles += &format!(
+            "{}",
+            format!("{}", letter)
+                .color(COLOUR_FG)
+                .bg_color(bg_color)
+                .bold()
+        );
+    }
+    tiles
+}
+
+fn print_feedback(guess: &str, feedback: &[Feedback]) {
+    println!("{}", format_feedback(guess, feedback));
+}
+
+fn print_candidates(candidates: &[String], list_below: usize) {
+    let text = match candidates.len() {
+        0 => String::from(" No words in the list match"),
+        1 => String::from(" 1 word left"),
+        n => format!(" {} words left", n),
+    };
+    if !candidates.is_empty() && candidates.len() < list_below {
+        println!("{}: {}", text.color(Color::LightGray), candidates.join(", "));
+    } else {
+        println!("{}", text.color(Color::LightGray));
+    }
+}
+
+/// Plays one game and returns it, with all the guesses that were made. The game isn't over if
+/// the input ran out first. With a deadline, the game is lost when the time is up.
+fn play(word: &String, guesses: &u32, args: &Args, input: &mut Input, deadline: Option<Instant>) -> Game {
     debug_print(format!("The word is: {}", word).as_str());
 
-    let mut guesses_left: u32 = *guesses;
+    // Words that still match all the feedback so far, for the counter under each row
+    let mut candidates = if args.candidates || args.list_below > 0 {
+        words_of_length(&get_word_list(args), word.chars().count())
+    } else {
+        Vec::new()
+    };
+
+    let best = if args.speedrun { personal_best(args, word.chars().count()) } else { None };
+    let mut game = Game::new(word, *guesses);
+    input::set_current_game(Some(&game));
     println!(
         "The word has {} letters, and you have {} guesses. Good luck!",
         word.len(),
         guesses
     );
 
-    while guesses_left > 0 {
+    while !game.is_over() {
         // Get input
-        let mut input = String::new();
-        print!("Your guess: ");
-        stdout().flush().unwrap();
-        std::io::stdin().read_line(&mut input).unwrap();
-        stdout().execute(cursor::MoveUp(1)).unwrap();
-        stdout()
-            .execute(terminal::Clear(ClearType::FromCursorDown))
-            .unwrap();
-        stdout().flush().unwrap();
+        let line = match deadline {
+            Some(deadline) => input.read_line_before(deadline, |left| {
+                format!("⏱️  {} Your guess: ", format_clock(left))
+            }),
+            None => {
+                if !input.is_scripted() {
+                    print!("Your guess: ");
+                    stdout().flush().unwrap();
+                }
+                input.read_line().map_or(Timed::End, Timed::Line)
+            }
+        };
+        let guess = match line {
+            Timed::Line(guess) => guess.to_lowercase(),
+            Timed::TimeUp => {
+                game.run_out_of_time();
+                break;
+            }
+            Timed::End => {
+                if !input.is_scripted() {
+                    println!();
+                }
+                input::set_current_game(None);
+                return game;
+            }
+        };
+        if !input.is_scripted() {
+            stdout().execute(cursor::MoveUp(1)).unwrap();
+            stdout()
+                .execute(terminal::Clear(ClearType::FromCursorDown))
+                .unwrap();
+            stdout().flush().unwrap();
+        }
 
-        input = input.trim().to_string();
+        // Process the guess and print the result
+        let turn = match game.guess(&guess) {
+            Ok(turn) => turn,
+            Err(GuessError::WrongLength { expected, got }) => {
+                println!(
+                    "The word has {} letters, but your guess had {}... 👀",
+                    expected, got
+                );
+                continue; // Don't count the guess, mistakes happen.
+            }
+            Err(GuessError::Over) => break,
+        };
+        print_feedback(&turn.guess, &turn.feedback);
+        input::set_current_game(Some(&game));
+        if args.speedrun {
+            print_split(&game, best.as_ref());
+        }
+        let turn = game.history.last().unwrap();
+        if (args.candidates || args.list_below > 0) && word != &guess {
+            candidates.retain(|w| is_consistent(w, &turn.guess, &turn.feedback));
+            print_candidates(&candidates, args.list_below);
+        }
+    }
 
-        // Wrong word length
-        if input.len() != word.len() {
+    // Check for a correct guess
+    input::set_current_game(None);
+    if game.is_won() {
+        if game.history.len() == 1 {
+            println!(" Holy 🐮, you got it on the first try! 🍀");
+        } else {
             println!(
-                "The word has {} letters, but your guess had {}... 👀",
-                word.len(),
-                input.len()
+                "You got it in {} guesses out of {}!\n Well done! 🥳",
+                game.history.len(),
+                guesses
             );
-            continue; // Don't count the guess, mistakes happen.
         }
-        // Process the guess and print the result
-        let mut available_letters = vec![true; word.len()];
-        for n in 0..word.len() {
-            let word_char = word.chars().nth(n).unwrap();
-            let input_char = input.chars().nth(n).unwrap();
-            if word_char == input_char {
-                print!(
-                    "{}",
-                    format!("{}", input_char)
-                        .color(COLOUR_FG)
-                        .bg_color(COLOUR_BG_CORRECT)
-                        .bold()
+    } else if game.is_out_of_time() {
+        println!("⏰ Time's up! The word was \"{}\"!", word);
+    } else {
+        println!("The word was \"{}\"!\n Better luck next time. 😔", word);
+    }
+    if args.speedrun {
+        finish_speedrun(args, &game, best.as_ref(), input.is_scripted());
+    }
+    show_definition(args, word);
+    game
+}
+
+/// The mode the game is played in, for the recordings and the personal bests.
+fn game_mode(args: &Args) -> &'static str {
+    if args.blitz {
+        "blitz"
+    } else if args.survival {
+        "survival"
+    } else if args.time.is_some() {
+        "timed"
+    } else {
+        "classic"
+    }
+}
+
+/// The fastest win so far with words this long, in this mode.
+fn personal_best(args: &Args, length: usize) -> Option<stats::Best> {
+    let loaded = stats::path(args.stats.as_deref()).and_then(|path| stats::Stats::load(&path));
+    match loaded {
+        Ok(stats) => stats.best(length, game_mode(args)).cloned(),
+        Err(message) => {
+            eprintln!("{}", message);
+            None
+        }
+    }
+}
+
+/// The time of the last guess, and how it compares to the same guess in the best game.
+fn print_split(game: &Game, best: Option<&stats::Best>) {
+    let time = *game.times.last().unwrap();
+    let split = best.and_then(|b| b.splits.get(game.times.len() - 1));
+    match split {
+        Some(&split) => println!(
+            "   ⏱️  {} {}",
+            stats::format_time(time),
+            stats::format_difference(time, Duration::from_millis(split))
+        ),
+        None => println!("   ⏱️  {}", stats::format_time(time)),
+    }
+}
+
+/// Whether the answer was given with --word or a challenge, so it's known before the game starts.
+fn known_answer(args: &Args) -> bool {
+    !args.word.is_empty() || !args.challenge.is_empty()
+}
+
+/// Shows how long every guess took, and keeps the game if it's a new personal best. Scripted
+/// games don't count, scripts are too quick, and neither do games whose answer was known.
+fn finish_speedrun(args: &Args, game: &Game, best: Option<&stats::Best>, scripted: bool) {
+    let Some(&total) = game.times.last() else {
+        return;
+    };
+    let mut previous = Duration::ZERO;
+    let guesses: Vec<String> = game
+        .times
+        .iter()
+        .map(|&time| {
+            let took = format!("{:.1}s", (time - previous).as_secs_f64());
+            previous = time;
+            took
+        })
+        .collect();
+    println!("Time: {} ({})", stats::format_time(total), guesses.join(", "));
+    if !game.is_won() || scripted || known_answer(args) {
+        return;
+    }
+
+    let mode = game_mode(args);
+    let saved = stats::path(args.stats.as_deref()).and_then(|path| {
+        let mut stats = stats::Stats::load(&path)?;
+        let new_best = stats.add_game(game, mode);
+        if new_best {
+            stats.save(&path)?;
+        }
+        Ok(new_best)
+    });
+    match saved {
+        Ok(true) => println!("🏆 New personal best for {} letters, {}!", game.length(), mode),
+        Ok(false) => {
+            if let Some(best) = best {
+                println!(
+                    "Your best is {}, \"{}\" in {} guesses.",
+                    stats::format_time(best.time()),
+                    best.word,
+                    best.splits.len()
                 );
-            } else {
-                let mut found = false;
-                for n in 0..word.len() {
-                    let word_char_i = word.chars().nth(n).unwrap();
-                    let input_char_i = input.chars().nth(n).unwrap();
-                    if !available_letters[n] {
-                        // this letter is already taken
-                        continue;
-                    } else if word_char_i == input_char_i {
-                        // this letter is correctly guessed, so it should be taken
-                        available_letters[n] = false;
-                    } else if word_char_i == input_char {
-                        // unclaimed matching letter
-                        print!(
-                            "{}",
-                            format!("{}", input_char)
-                                .color(COLOUR_FG)
-                                .bg_color(COLOUR_BG_MISPLACED)
-                                .bold()
-                        );
-                        available_letters[n] = false;
-                        found = true;
-                        break;
-                    }
-                }
-                if !found {
-                    print!(
-                        "{}",
-                        format!("{}", input_char)
-                            .color(COLOUR_FG)
-                            .bg_color(COLOUR_BG_WRONG)
-                            .bold()
-                    );
-                }
             }
         }
-        println!();
+        Err(message) => eprintln!("{}", message),
+    }
+}
+
+/// Minutes and seconds, rounded up so the clock only shows 0:00 when the time is up.
+fn format_clock(left: Duration) -> String {
+    let seconds = (left.as_millis() as u64).div_ceil(1000);
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// When the time for a game started now is up, if it's played against the clock.
+fn time_limit(args: &Args) -> Option<Instant> {
+    args.time.map(|seconds| Instant::now() + Duration::from_secs(seconds))
+}
+
+fn assist(options: &SolverOptions, words: &[String], turns: &[String], length: usize) -> Result<(), String> {
+    let constraints = filter::Constraints::from_turns(turns)?;
+    let history = &constraints.history;
+    let words = words_of_length(words, constraints.length().unwrap_or(length));
+    let candidates = constraints.filter(&words);
+
+    match candidates.len() {
+        0 => println!("No word in the list matches that feedback. 🤔"),
+        1 => println!("It has to be \"{}\"!", candidates[0]),
+        n => {
+            let mut solver = solver::prepare(&options.strategy, &words, options.matrix)?;
+            println!(
+                "{} words left, try \"{}\"",
+                n,
+                solver.next_guess(&words, &candidates, history)?
+            )
+        }
+    }
+    Ok(())
+}
+
+/// Prints the words from the list that fit all the constraints, one on each line.
+fn filter_words(
+    args: &Args,
+    turns: &[String],
+    pattern: Option<&str>,
+    include: &str,
+    exclude: &str,
+    length: Option<usize>,
+    sort: FilterSort,
+) -> Result<(), String> {
+    let mut constraints = filter::Constraints::from_turns(turns)?.with_letters(include, exclude);
+    if let Some(pattern) = pattern {
+        constraints = constraints.with_pattern(pattern)?;
+    }
+    let mut words = get_word_list(args);
+    if let Some(length) = constraints.length().or(length) {
+        words = words_of_length(&words, length);
+    }
+    let mut matches = constraints.filter(&words);
+
+    match sort {
+        FilterSort::List => {}
+        FilterSort::Alphabetical => matches.sort(),
+        FilterSort::Frequency => {
+            let value = solver::letter_frequency(&matches);
+            matches.sort_by_key(|w| std::cmp::Reverse(value(w)));
+        }
+        FilterSort::Entropy => {
+            let mut scored: Vec<(f64, String)> = matches
+                .par_iter()
+                .map(|w| (analysis::expected_information(w, &matches), w.clone()))
+                .collect();
+            scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+            matches = scored.into_iter().map(|(_, w)| w).collect();
+        }
+    }
 
-        guesses_left -= 1;
-        // Check for a correct guess
-        if word == &input {
-            if guesses - guesses_left == 1 {
-                println!(" Holy 🐮, you got it on the first try! 🍀");
+    if matches.is_empty() {
+        eprintln!("No words in the list fit. 🤔");
+    }
+    for word in &matches {
+        println!("{}", word);
+    }
+    Ok(())
+}
+
+/// Prints the feedback for every guess, scored the same way as in the game.
+fn score_guesses(answer: &str, guesses: &[String], format: ScoreFormat) -> Result<(), String> {
+    let answer = answer.to_lowercase();
+    let length = answer.chars().count();
+    if let Some(guess) = guesses.iter().find(|g| g.chars().count() != length) {
+        return Err(format!("The guess \"{}\" doesn't have {} letters like \"{}\"", guess, length, answer));
+    }
+    for guess in guesses.iter().map(|g| g.to_lowercase()) {
+        let feedback = score(&answer, &guess);
+        match format {
+            ScoreFormat::Colour => print_feedback(&guess, &feedback),
+            ScoreFormat::Symbols => println!("{}", scoring::symbol_string(&feedback)),
+            ScoreFormat::Pattern => println!("{} {}", guess, scoring::pattern_string(&feedback)),
+        }
+    }
+    Ok(())
+}
+
+fn show_analysis(answer: &str, history: &[Turn], words: &[String], matrix: bool) -> Result<(), String> {
+    let length = answer.chars().count();
+    if let Some(turn) = history.iter().find(|t| t.guess.chars().count() != length) {
+        return Err(format!("The guess \"{}\" doesn't have {} letters", turn.guess, length));
+    }
+    let words = words_of_length(words, length);
+    analysis::print_analysis(&analysis::analyze(answer, history, &words, matrix)?);
+    Ok(())
+}
+
+fn create_challenge(args: &Args, word: &str) {
+    let word = word.to_lowercase();
+    let words = get_word_list(args);
+    if !words.contains(&word) {
+        println!("Heads up: \"{}\" isn't in the word list.", word);
+    }
+    let code = challenge::Challenge {
+        word,
+        list_hash: patterns::list_hash(&words) as u32,
+        guesses: args.guesses(),
+        mode: challenge::MODE_CLASSIC,
+    }
+    .encode();
+    println!("Send this to your friend, and tell them to run:\n rustle --challenge {}", code);
+}
+
+/// Plays the challenge, and returns whether it was won.
+fn play_challenge(args: &Args, input: &mut Input) -> Result<bool, String> {
+    if args.time.is_some() && args.protocol == Protocol::Jsonl {
+        return Err(String::from("Playing against the clock only works with the text protocol"));
+    }
+    let challenge = challenge::Challenge::decode(&args.challenge)?;
+    if challenge.list_hash != patterns::list_hash(&get_word_list(args)) as u32 {
+        let message = "This challenge was made with a different word list, but the word is in the code.";
+        // Stdout is for the events in the jsonl protocol
+        match args.protocol {
+            Protocol::Text => println!("{}", message),
+            Protocol::Jsonl => eprintln!("{}", message),
+        }
+    }
+    match challenge.mode {
+        challenge::MODE_CLASSIC => {
+            let game = if args.protocol == Protocol::Jsonl {
+                protocol::play_jsonl(&challenge.word, challenge.guesses, input)
             } else {
-                println!(
-                    "You got it in {} guesses out of {}!\n Well done! 🥳",
-                    guesses - guesses_left,
-                    guesses
-                );
+                println!("Challenge accepted! 🤺");
+                play(&challenge.word, &challenge.guesses, args, input, time_limit(args))
+            };
+            if !game.is_over() {
+                quit_game(args, &game);
+                if input.is_scripted() {
+                    return Err(String::from("The input ended before the game was over"));
+                }
+                return Ok(false);
             }
-            return;
+            finish_game(args, &game);
+            Ok(game.is_won())
         }
+        _ => unreachable!("unknown modes are rejected when decoding"),
     }
-    println!("The word was \"{}\"!\n Better luck next time. 😔", word);
+}
+
+fn demo(options: &SolverOptions, word: &str, words: &[String], guesses: u32) -> Result<(), String> {
+    debug_print(format!("The word is: {}", word).as_str());
+    let length = word.chars().count();
+    let mut words = words_of_length(words, length);
+    if !words.iter().any(|w| w == word) {
+        words.push(word.to_string());
+    }
+    let mut solver = solver::prepare(&options.strategy, &words, options.matrix)?;
+    let mut candidates = words.clone();
+    let mut history: Vec<Turn> = Vec::new();
+
+    println!(
+        "The word has {} letters, and the solver has {} guesses. Let's watch! 🍿",
+        length, guesses
+    );
+    while (history.len() as u32) < guesses {
+        thread::sleep(DEMO_DELAY);
+        let guess = solver.next_guess(&words, &candidates, &history)?;
+        let feedback = score(word, &guess);
+        print_feedback(&guess, &feedback);
+        if guess == word {
+            println!(
+                "The solver got it in {} guesses out of {}! 🤖",
+                history.len() + 1,
+                guesses
+            );
+            return Ok(());
+        }
+        candidates.retain(|w| is_consistent(w, &guess, &feedback));
+        history.push(Turn { guess, feedback });
+    }
+    println!("The word was \"{}\"!\n The solver ran out of guesses. 🤖", word);
+    Ok(())
 }
 
 fn debug_print(_text: &str) {
+    // On stderr, so it stays out of piped output
     #[cfg(debug_assertions)]
-    println!("{}", _text.color(Color::White).bg_color(Color::Red));
+    eprintln!("{}", _text.color(Color::White).bg_color(Color::Red));
 }
 
 fn main() {
     let args = Args::parse();
     debug_print("Debug mode is enabled");
+    let interrupted_args = args.clone();
+    input::catch_interrupts(move |game| {
+        quit_game(&interrupted_args, game);
+        if let Some(session) = SESSION.lock().unwrap().take() {
+            end_session(&interrupted_args, &session, game);
+        }
+    });
+
+    // Set by the games, for the exit code
+    let mut lost = false;
+    let result = match &args.command {
+        Some(Command::Bench { solver }) => bench::run(
+            &solver.strategy,
+            solver.matrix,
+            &get_word_list(&args),
+            args.guesses(),
+        ),
+        Some(Command::Assist {
+            solver,
+            length,
+            turns,
+        }) => assist(solver, &get_word_list(&args), turns, *length),
+        Some(Command::Filter {
+            pattern,
+            include,
+            exclude,
+            length,
+            sort,
+            turns,
+        }) => filter_words(&args, turns, pattern.as_deref(), include, exclude, *length, *sort),
+        Some(Command::Score {
+            format,
+            answer,
+            played,
+        }) => score_guesses(answer, played, *format),
+        Some(Command::Analyze {
+            matrix,
+            answer,
+            played,
+        }) => {
+            let answer = answer.to_lowercase();
+            let history: Vec<Turn> = played
+                .iter()
+                .map(|guess| Turn {
+                    guess: guess.to_lowercase(),
+                    feedback: score(&answer, &guess.to_lowercase()),
+                })
+                .collect();
+            show_analysis(&answer, &history, &get_word_list(&args), *matrix)
+        }
+        Some(Command::Reverse { solver, length }) => {
+            let words = words_of_length(&get_word_list(&args), *length);
+            Input::open(args.script.as_deref()).and_then(|mut input| {
+                solver::prepare(&solver.strategy, &words, solver.matrix)
+                    .and_then(|mut solver| reverse::play_reverse(solver.as_mut(), &words, args.guesses(), &mut input))
+            })
+        }
+        Some(Command::Hotseat) if args.protocol == Protocol::Jsonl => {
+            Err(String::from("Hot-seat only works with the text protocol"))
+        }
+        Some(Command::Hotseat) => {
+            Input::open(args.script.as_deref()).map(|mut input| hotseat::play_hotseat(&args, &mut input))
+        }
+        Some(Command::Challenge {
+            action: ChallengeAction::Create { word },
+        }) => {
+            create_challenge(&args, word);
+            Ok(())
+        }
+        None if !args.challenge.is_empty() => Input::open(args.script.as_deref())
+            .and_then(|mut input| play_challenge(&args, &mut input))
+            .map(|won| lost = !won),
+        Some(Command::Host { port, name }) => Input::open(args.script.as_deref()).and_then(|mut input| {
+            race::host(*port, pick_word(&args, &mut word_rng(&args)), args.guesses(), name, &mut input)
+        }),
+        Some(Command::Join { address, name }) => {
+            Input::open(args.script.as_deref()).and_then(|mut input| race::join(address, name, &mut input))
+        }
+        Some(Command::Serve { port, lists_dir }) => {
+            let internal = INTERNAL_WORD_LIST.iter().map(|w| w.to_string()).collect();
+            let list_file = Some((args.list_file.as_str(), args.separator.as_str()))
+                .filter(|(path, _)| !path.is_empty());
+            blocklist::load(args.family_friendly, &args.blocklist)
+                .and_then(|blocked| {
+                    serve::GameServer::new(internal, list_file, lists_dir.as_deref(), args.guesses(), blocked)
+                })
+                .and_then(|mut server| server.run(*port))
+        }
+        Some(Command::Replay { file, speed, step }) => {
+            let recording = Input::open(args.script.as_deref())
+                .and_then(|input| record::Recording::load(file).map(|recording| (recording, input)));
+            recording.and_then(|(recording, mut input)| {
+                if !speed.is_finite() || *speed <= 0.0 {
+                    return Err(String::from("The speed has to be a number more than 0"));
+                }
+                record::replay(&recording, *speed, *step, &mut input);
+                if args.analyze {
+                    let words = match recording.settings.list.as_str() {
+                        "internal" => INTERNAL_WORD_LIST.iter().map(|w| w.to_string()).collect(),
+                        path if fs::metadata(path).is_ok() => load_word_list(path, &args.separator),
+                        _ => get_word_list(&args),
+                    };
+                    show_analysis(&recording.answer, &recording.history(), &words, args.analyze_matrix)?;
+                }
+                Ok(())
+            })
+        }
+        Some(Command::Define { words }) => words.iter().try_for_each(|word| {
+            if !define_word(&args, &word.to_lowercase(), true)? {
+                println!(" \"{}\" isn't in the dictionary. 📖", word);
+            }
+            Ok(())
+        }),
+        Some(Command::Stats) => stats::path(args.stats.as_deref()).and_then(|path| stats::show(&path)),
+        Some(Command::Lists { action }) => match action {
+            ListsAction::Validate { file, length } => lists::validate(file, *length),
+            ListsAction::Dedupe { file, output } => lists::dedupe(file, output.as_deref()),
+            ListsAction::SplitByLength { file, out_dir } => lists::split_by_length(file, out_dir),
+            ListsAction::Merge { files, output } => lists::merge(files, output),
+            ListsAction::Stats { file } => lists::stats(file),
+        },
+        Some(Command::Demo { solver }) => {
+            demo(solver, &pick_word(&args, &mut word_rng(&args)), &get_word_list(&args), args.guesses())
+        }
+        None => Input::open(args.script.as_deref())
+            .and_then(|mut input| play_loop(&args, &mut input))
+            .map(|won| lost = !won),
+    };
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        std::process::exit(2);
+    }
+    if lost {
+        std::process::exit(1);
+    }
+}
+
+/// Plays games until the player is done, and returns whether all of them were won.
+fn play_loop(args: &Args, input: &mut Input) -> Result<bool, String> {
+    if args.time.is_some() && args.protocol == Protocol::Jsonl {
+        return Err(String::from("Playing against the clock only works with the text protocol"));
+    }
+    if args.survival && args.protocol == Protocol::Jsonl {
+        return Err(String::from("Survival only works with the text protocol"));
+    }
+    let mut rng = word_rng(args);
+    let mut won = true;
+    let mut played = 0;
+    // In a blitz, the clock runs for the whole session instead of for every word
+    let blitz = if args.blitz { time_limit(args) } else { None };
+    let mut session = Session {
+        solved: 0,
+        played: 0,
+        started: Instant::now(),
+        scripted: input.is_scripted(),
+    };
+    if args.blitz || args.survival {
+        *SESSION.lock().unwrap() = Some(session);
+    }
+    // In survival, what's left of the guesses goes on to the next word
+    let mut guesses = args.guesses();
     loop {
-        if args.word != "" {
-            debug_print("Word source: given word");
-            play(&args.word, &args.guesses);
-        } else if args.list_file != "" {
-            debug_print("Word source: external word list");
-            play(&get_word_from_list(&args.list_file, &args.separator), &args.guesses);
+        let game = if args.protocol == Protocol::Jsonl {
+            protocol::play_jsonl(&pick_word(args, &mut rng), args.guesses(), input)
         } else {
-            debug_print("Word source: internal word list");   
-            play(&get_word_from_internal(), &args.guesses);
+            let deadline = blitz.or_else(|| time_limit(args));
+            play(&pick_word(args, &mut rng), &guesses, args, input, deadline)
+        };
+        if !game.is_over() {
+            quit_game(args, &game);
+            // Quitting ends a blitz or a survival run like the clock or a lost word does
+            if let Some(session) = SESSION.lock().unwrap().take() {
+                end_session(args, &session, &game);
+                return Ok(session.solved > 0);
+            }
+            // Running out of input between games is just the end of the session
+            if game.history.is_empty() && played > 0 {
+                return Ok(won);
+            }
+            if input.is_scripted() {
+                return Err(String::from("The input ended before the game was over"));
+            }
+            // The player gave up
+            return Ok(false);
         }
-        
-        if args.noreplay{
-            break;
-        }else if args.replay{
+        finish_game(args, &game);
+        won &= game.is_won();
+        played += 1;
+
+        if args.blitz {
+            session.solved += game.is_won() as u32;
+            session.played += 1;
+            if game.is_out_of_time() {
+                SESSION.lock().unwrap().take();
+                end_session(args, &session, &game);
+                return Ok(session.solved > 0);
+            }
+            *SESSION.lock().unwrap() = Some(session);
+            // Asking would only eat into the time
             println!("\nNext word!");
-            continue
-        }else{
-            let mut input = String::new();
-            print!("Play again? (Y/N): ");
-            stdout().flush().unwrap();
-            std::io::stdin().read_line(&mut input).unwrap();
-            if input[..1].to_lowercase() != "y" {
-                break;
+            continue;
+        }
+
+        if args.survival {
+            session.played += 1;
+            if !game.is_won() {
+                SESSION.lock().unwrap().take();
+                end_session(args, &session, &game);
+                return Ok(session.solved > 0);
             }
+            session.solved += 1;
+            *SESSION.lock().unwrap() = Some(session);
+            guesses = game.guesses_left() + SURVIVAL_BONUS;
+            println!(
+                "\n{} down! {} guesses left over, plus {} for the next word.",
+                session.solved,
+                game.guesses_left(),
+                SURVIVAL_BONUS
+            );
+            continue;
+        }
+
+        let again = if args.protocol == Protocol::Jsonl {
+            // Scripts get one game, unless they ask for more up front
+            args.replay
+        } else {
+            play_again(args, input)
+        };
+        if !again {
+            return Ok(won);
+        }
+    }
+}
+
+/// Wraps up a blitz or a survival run, however it ended. The game is the last one, over or not.
+fn end_session(args: &Args, session: &Session, game: &Game) {
+    if args.blitz {
+        // A word that was started and left counts as one that wasn't found
+        let played = session.played + (!game.is_over() && !game.history.is_empty()) as u32;
+        let time = session.started.elapsed().min(Duration::from_secs(args.time.unwrap()));
+        println!(
+            "\n🏁 You found {} of {} words in {} seconds!",
+            session.solved,
+            played,
+            time.as_secs()
+        );
+    } else {
+        end_survival(args, session, game.length());
+    }
+}
+
+/// Shows the score of a survival run, and keeps it if it's one of the high scores. Scripted runs
+/// don't count, and neither do runs with a known answer, it would be the same word every time.
+fn end_survival(args: &Args, session: &Session, length: usize) {
+    println!("\n💀 The run is over, you found {} words.", session.solved);
+    let solved = session.solved;
+    if solved == 0 || session.scripted || known_answer(args) {
+        return;
+    }
+    let saved = stats::path(args.stats.as_deref()).and_then(|path| {
+        let mut stats = stats::Stats::load(&path)?;
+        let place = stats.add_high_score(solved, length);
+        if place.is_some() {
+            stats.save(&path)?;
+        }
+        Ok(place)
+    });
+    match saved {
+        Ok(Some(0)) => println!("🏆 New high score!"),
+        Ok(Some(place)) => println!("That's number {} on your high scores! 💪", place + 1),
+        Ok(None) => {}
+        Err(message) => eprintln!("{}", message),
+    }
+}
+
+/// What happens after every game: the analysis and the recording, if asked for.
+fn finish_game(args: &Args, game: &Game) {
+    if args.analyze && args.protocol == Protocol::Text {
+        if let Err(message) = show_analysis(&game.word, &game.history, &get_word_list(args), args.analyze_matrix) {
+            println!("{}", message);
         }
     }
+    record_game(args, game);
+}
+
+/// Wraps up a game that was left before it was over, by Ctrl-C or the end of the input.
+fn quit_game(args: &Args, game: &Game) {
+    if args.protocol == Protocol::Jsonl {
+        protocol::emit(&protocol::Event::GameOver {
+            result: "quit",
+            guesses: game.history.len(),
+            answer: &game.word,
+        });
+    } else if !game.history.is_empty() {
+        println!(
+            "Stopped after {} of {} guesses, the word was \"{}\". 👋",
+            game.history.len(),
+            game.guesses,
+            game.word
+        );
+    }
+    if !game.history.is_empty() {
+        record_game(args, game);
+    }
+}
+
+fn record_game(args: &Args, game: &Game) {
+    if let Some(dir) = &args.record {
+        let list = if args.list_file.is_empty() { "internal" } else { &args.list_file };
+        match record::Recording::from_game(game, list, game_mode(args)).save_in(dir) {
+            // Stdout is for the events in the jsonl protocol
+            Ok(path) if args.protocol == Protocol::Jsonl => eprintln!("Game recorded in {}", path.display()),
+            Ok(path) => println!("Game recorded in {} 📼", path.display()),
+            Err(message) => eprintln!("{}", message),
+        }
+    }
+}
+
+fn play_again(args: &Args, input: &mut Input) -> bool {
+    if args.noreplay{
+        false
+    }else if args.replay{
+        println!("\nNext word!");
+        true
+    }else if input.is_scripted(){
+        // Scripts are full of guesses, not answers to this question
+        false
+    }else{
+        print!("Play again? (Y/N): ");
+        stdout().flush().unwrap();
+        input.read_line().is_some_and(|answer| answer.to_lowercase().starts_with('y'))
+    }
 }
 
 const INTERNAL_LIST_LENGTH: usize = 4266;
-const INTERNAL_WORD_LIST: [&'static str; INTERNAL_LIST_LENGTH] = [
+static INTERNAL_WORD_LIST: [&str; INTERNAL_LIST_LENGTH] = [
     "aaron", "aback", "abaft", "abase", "abash", "abate", "abbey", "abbot", "abeam", "abele",
     "abets", "abhor", "abide", "abies", "abler", "abode", "abomb", "abort", "about", "above",
     "abuse", "abuzz", "abyss", "accra", "acers", "ached", "aches", "acids", "acorn", "acres",
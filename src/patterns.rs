@@ -0,0 +1,117 @@
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::debug_print;
+use crate::scoring::{score_pattern, Pattern};
+
+// Every pattern has to fit in 16 bits, 3^10 is the most that does
+pub const MAX_MATRIX_LENGTH: usize = 10;
+const MATRIX_FILE_MAGIC: &[u8; 8] = b"RSTLPM1\0";
+
+// --- Precomputed feedback patterns for every guess against every answer ---
+pub struct PatternMatrix {
+    words: Vec<String>,
+    index: HashMap<String, usize>,
+    patterns: Vec<u16>,
+}
+
+impl PatternMatrix {
+    /// Scores every word against every other word. The words must all have the same length, of
+    /// at most `MAX_MATRIX_LENGTH` letters.
+    pub fn build(words: &[String]) -> PatternMatrix {
+        let patterns: Vec<u16> = words
+            .par_iter()
+            .flat_map_iter(|guess| words.iter().map(move |answer| score_pattern(answer, guess) as u16))
+            .collect();
+        PatternMatrix::from_parts(words, patterns)
+    }
+
+    /// Loads the matrix for this word list from the cache, or builds and caches it if it isn't
+    /// there yet. Lists are told apart by a hash of their contents.
+    pub fn load_or_build(words: &[String]) -> PatternMatrix {
+        let path = cache_path(words);
+        if let Some(patterns) = path.as_ref().and_then(|p| read_patterns(p, words.len())) {
+            debug_print(format!("Pattern matrix loaded from {}", path.unwrap().display()).as_str());
+            return PatternMatrix::from_parts(words, patterns);
+        }
+
+        println!("Building the pattern matrix for {} words...", words.len());
+        let matrix = PatternMatrix::build(words);
+        if let Some(path) = path {
+            if let Err(e) = matrix.write(&path) {
+                println!("Couldn't cache the pattern matrix in {}: {}", path.display(), e);
+            }
+        }
+        matrix
+    }
+
+    fn from_parts(words: &[String], patterns: Vec<u16>) -> PatternMatrix {
+        let index = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (w.clone(), i))
+            .collect();
+        PatternMatrix {
+            words: words.to_vec(),
+            index,
+            patterns,
+        }
+    }
+
+    pub fn index_of(&self, word: &str) -> Option<usize> {
+        self.index.get(word).copied()
+    }
+
+    pub fn get(&self, guess: usize, answer: usize) -> Pattern {
+        self.patterns[guess * self.words.len() + answer] as Pattern
+    }
+
+    fn write(&self, path: &PathBuf) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut data = Vec::with_capacity(MATRIX_FILE_MAGIC.len() + 8 + self.patterns.len() * 2);
+        data.extend_from_slice(MATRIX_FILE_MAGIC);
+        data.extend_from_slice(&(self.words.len() as u64).to_le_bytes());
+        for pattern in &self.patterns {
+            data.extend_from_slice(&pattern.to_le_bytes());
+        }
+        fs::write(path, data)
+    }
+}
+
+fn read_patterns(path: &PathBuf, words: usize) -> Option<Vec<u16>> {
+    let data = fs::read(path).ok()?;
+    let (header, body) = data.split_at_checked(MATRIX_FILE_MAGIC.len() + 8)?;
+    if &header[..MATRIX_FILE_MAGIC.len()] != MATRIX_FILE_MAGIC
+        || header[MATRIX_FILE_MAGIC.len()..] != (words as u64).to_le_bytes()
+        || body.len() != words * words * 2
+    {
+        debug_print(format!("Ignoring damaged pattern matrix {}", path.display()).as_str());
+        return None;
+    }
+    Some(
+        body.chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .collect(),
+    )
+}
+
+fn cache_path(words: &[String]) -> Option<PathBuf> {
+    let dir = dirs::cache_dir()?.join("rustle");
+    Some(dir.join(format!("patterns-{:016x}.bin", list_hash(words))))
+}
+
+/// FNV-1a hash of the words in order, which stays the same between builds and platforms.
+pub fn list_hash(words: &[String]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for word in words {
+        for byte in word.bytes().chain(std::iter::once(b'\n')) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::io::{stdout, Write};
+
+use crate::game::{Game, GuessError};
+use crate::input::{self, Input};
+use crate::scoring::{pattern_string, Feedback};
+
+// --- JSON lines protocol, for scripts and bots ---
+//
+// Every line rustle writes is one event object, every line it reads is a guess, either as
+// {"guess": "crane"} or just "crane". There are no colours or cursor tricks, so it's pipe friendly.
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    GameStart {
+        length: usize,
+        guesses: u32,
+    },
+    Feedback {
+        guess: &'a str,
+        feedback: &'a [Feedback],
+        pattern: String,
+        guesses_left: u32,
+    },
+    InvalidGuess {
+        guess: Option<&'a str>,
+        reason: String,
+    },
+    GameOver {
+        result: &'a str,
+        guesses: usize,
+        answer: &'a str,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GuessLine {
+    Object { guess: String },
+    Bare(String),
+}
+
+pub fn emit(event: &Event) {
+    let mut out = stdout();
+    writeln!(out, "{}", serde_json::to_string(event).unwrap()).unwrap();
+    out.flush().unwrap();
+}
+
+/// Plays one game over the protocol. The game isn't over if the input ran out first, and then it's
+/// up to the caller to tell the other side.
+pub fn play_jsonl(word: &str, guesses: u32, input: &mut Input) -> Game {
+    let mut game = Game::new(word, guesses);
+    emit(&Event::GameStart {
+        length: game.length(),
+        guesses,
+    });
+    input::set_current_game(Some(&game));
+
+    while !game.is_over() {
+        let line = match input.read_line() {
+            Some(line) => line,
+            None => {
+                input::set_current_game(None);
+                return game;
+            }
+        };
+        if line.is_empty() {
+            continue;
+        }
+        let guess = match serde_json::from_str::<GuessLine>(&line) {
+            Ok(GuessLine::Object { guess }) | Ok(GuessLine::Bare(guess)) => guess.trim().to_lowercase(),
+            Err(e) => {
+                emit(&Event::InvalidGuess {
+                    guess: None,
+                    reason: format!("Invalid JSON: {}", e),
+                });
+                continue;
+            }
+        };
+
+        match game.guess(&guess) {
+            Ok(turn) => {
+                let (guess, feedback) = (turn.guess.clone(), turn.feedback.clone());
+                emit(&Event::Feedback {
+                    guess: &guess,
+                    feedback: &feedback,
+                    pattern: pattern_string(&feedback),
+                    guesses_left: game.guesses_left(),
+                });
+                input::set_current_game(Some(&game));
+            }
+            Err(e @ GuessError::WrongLength { .. }) | Err(e @ GuessError::Over) => {
+                emit(&Event::InvalidGuess {
+                    guess: Some(&guess),
+                    reason: e.to_string(),
+                });
+            }
+        }
+    }
+
+    input::set_current_game(None);
+    emit(&Event::GameOver {
+        result: if game.is_won() { "won" } else { "lost" },
+        guesses: game.history.len(),
+        answer: word,
+    });
+    game
+}
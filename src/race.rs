@@ -0,0 +1,372 @@
+use std::io::{stdout, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use colorful::Colorful;
+use crossterm::{terminal, terminal::ClearType, ExecutableCommand};
+
+use crate::input::Input;
+use crate::scoring::{parse_pattern, pattern_string, score, Feedback};
+use crate::{debug_print, format_feedback, print_feedback};
+
+// --- Race mode: players on a network guess the same word, first to solve it wins ---
+//
+// The host keeps the word to itself and scores every guess, so it never reaches the players
+// before the game is over. Everything is sent as text lines:
+//
+//   player -> host: NAME <name>, GUESS <word>
+//   host -> player: JOINED <name>, LEFT <name>, START <length> <guesses>, FEEDBACK <pattern>,
+//                   INVALID <reason>, PROGRESS <name> <pattern>, OUT, WINNER <name> <guesses>,
+//                   OVER <word>
+
+// The race, and a condvar that's notified when a player joins and whenever it might be over
+type SharedRace = Arc<(Mutex<Race>, Condvar)>;
+// What the player's side does once the host says the race is over, or is gone
+type Finish = Box<dyn FnOnce(i32) + Send>;
+
+// Whether the guess prompt is on screen, so it can be put back after an announcement
+static PROMPTING: AtomicBool = AtomicBool::new(false);
+
+struct Player {
+    name: String,
+    stream: TcpStream,
+    guesses: u32,
+    done: bool,
+}
+
+struct Race {
+    word: String,
+    guesses: u32,
+    started: bool,
+    over: bool,
+    players: Vec<Player>,
+}
+
+impl Race {
+    fn send(&mut self, player: usize, message: &str) {
+        let _ = writeln!(self.players[player].stream, "{}", message);
+    }
+
+    fn broadcast(&mut self, message: &str) {
+        for player in 0..self.players.len() {
+            self.send(player, message);
+        }
+    }
+
+    /// Ends the game once there is a winner, or when nobody has guesses left.
+    fn check_over(&mut self) {
+        if self.started && !self.over && self.players.iter().all(|p| p.done) {
+            self.over = true;
+            let word = self.word.clone();
+            self.broadcast(&format!("OVER {}", word));
+        }
+    }
+
+    fn guess(&mut self, player: usize, guess: &str) {
+        if !self.started || self.over || self.players[player].done {
+            return self.send(player, "INVALID the game isn't running");
+        }
+        let length = self.word.chars().count();
+        if guess.chars().count() != length {
+            return self.send(player, &format!("INVALID the word has {} letters", length));
+        }
+
+        let feedback = score(&self.word, guess);
+        let pattern = pattern_string(&feedback);
+        self.players[player].guesses += 1;
+        self.send(player, &format!("FEEDBACK {}", pattern));
+        let name = self.players[player].name.clone();
+        for other in 0..self.players.len() {
+            if other != player {
+                self.send(other, &format!("PROGRESS {} {}", name, pattern));
+            }
+        }
+
+        if guess == self.word {
+            let guesses = self.players[player].guesses;
+            self.broadcast(&format!("WINNER {} {}", name, guesses));
+            for p in self.players.iter_mut() {
+                p.done = true;
+            }
+        } else if self.players[player].guesses >= self.guesses {
+            self.players[player].done = true;
+            self.send(player, "OUT");
+        }
+        self.check_over();
+    }
+
+    fn leave(&mut self, player: usize) {
+        self.players[player].done = true;
+        let name = self.players[player].name.clone();
+        self.broadcast(&format!("LEFT {}", name));
+        self.check_over();
+    }
+}
+
+/// Runs a race: waits for players until the host presses enter, then plays along on the same
+/// computer, through the network like everybody else. The host's process is also the server, so
+/// it keeps going until the race is over and everybody has been told.
+pub fn host(port: u16, word: String, guesses: u32, name: &str, input: &mut Input) -> Result<(), String> {
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .map_err(|e| format!("Can't listen on port {}: {}", port, e))?;
+    let race: SharedRace = Arc::new((
+        Mutex::new(Race {
+            word,
+            guesses,
+            started: false,
+            over: false,
+            players: Vec::new(),
+        }),
+        Condvar::new(),
+    ));
+
+    let accepting = race.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let race = accepting.clone();
+            thread::spawn(move || serve_player(race, stream));
+        }
+    });
+
+    println!("Hosting a race on port {}, others can join with: rustle join <your address>:{}", port, port);
+    let finishing = race.clone();
+    let finish: Finish = Box::new(move |code| {
+        wait_until_over(&finishing);
+        std::process::exit(code);
+    });
+    let connection = Connection::open(&format!("127.0.0.1:{}", port), name, finish)?;
+    {
+        // The host has to be in before the race can start
+        let (race, joined) = &*race;
+        let _race = joined.wait_while(race.lock().unwrap(), |race| race.players.is_empty()).unwrap();
+    }
+    print!("Press enter to start when everyone has joined.\n\n");
+    stdout().flush().unwrap();
+    // Scripts start with the first line, which can be anything
+    input.read_line();
+
+    {
+        let mut race = race.0.lock().unwrap();
+        race.started = true;
+        let length = race.word.chars().count();
+        race.broadcast(&format!("START {} {}", length, guesses));
+    }
+    connection.play(input);
+    // The host gave up, but the others may still be playing
+    println!("Waiting for the others to finish... ⏳");
+    connection.wait_until_over();
+    wait_until_over(&race);
+    Ok(())
+}
+
+/// Waits until the race is over. The end is broadcast while the race is locked, so by then
+/// everybody has been told.
+fn wait_until_over(race: &SharedRace) {
+    let (race, over) = &**race;
+    let _race = over.wait_while(race.lock().unwrap(), |race| !race.over).unwrap();
+}
+
+fn serve_player(shared: SharedRace, stream: TcpStream) {
+    let (race, over) = &*shared;
+    let reader = BufReader::new(stream.try_clone().unwrap());
+    let mut player: Option<usize> = None;
+
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let (command, argument) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        let mut race = race.lock().unwrap();
+        match (command, player) {
+            ("NAME", None) => {
+                if race.started {
+                    let _ = writeln!(&stream, "INVALID the race has already started");
+                    return;
+                }
+                let name = match argument.split_whitespace().next() {
+                    Some(name) => name.to_string(),
+                    None => format!("player{}", race.players.len() + 1),
+                };
+                debug_print(format!("{} joined the race", name).as_str());
+                race.broadcast(&format!("JOINED {}", name));
+                for existing in race.players.iter().map(|p| p.name.clone()).collect::<Vec<_>>() {
+                    let _ = writeln!(&stream, "JOINED {}", existing);
+                }
+                race.players.push(Player {
+                    name,
+                    stream: stream.try_clone().unwrap(),
+                    guesses: 0,
+                    done: false,
+                });
+                player = Some(race.players.len() - 1);
+                over.notify_all();
+            }
+            ("GUESS", Some(player)) => {
+                race.guess(player, &argument.trim().to_lowercase());
+                over.notify_all();
+            }
+            _ => {
+                let _ = writeln!(&stream, "INVALID unknown message");
+            }
+        }
+    }
+    if let Some(player) = player {
+        race.lock().unwrap().leave(player);
+        over.notify_all();
+    }
+}
+
+enum Message {
+    Start(usize, u32),
+    Feedback(Vec<Feedback>),
+    Invalid(String),
+    Over,
+}
+
+/// A player's side of the race.
+struct Connection {
+    stream: TcpStream,
+    messages: Receiver<Message>,
+}
+
+impl Connection {
+    /// Connects to the host. `finish` is called with the exit code when the race is over or the
+    /// host is gone.
+    fn open(address: &str, name: &str, finish: Finish) -> Result<Connection, String> {
+        let stream = TcpStream::connect(address)
+            .map_err(|e| format!("Can't connect to {}: {}", address, e))?;
+        writeln!(&stream, "NAME {}", name).map_err(|e| e.to_string())?;
+
+        let (sender, messages) = channel();
+        let reader = BufReader::new(stream.try_clone().unwrap());
+        thread::spawn(move || listen(reader, sender, finish));
+        Ok(Connection { stream, messages })
+    }
+
+    fn play(&self, input: &mut Input) {
+        let (length, guesses) = loop {
+            match self.messages.recv() {
+                Ok(Message::Start(length, guesses)) => break (length, guesses),
+                Ok(Message::Invalid(reason)) => {
+                    println!("The host says: {}", reason);
+                    return;
+                }
+                Ok(Message::Over) | Err(_) => return,
+                Ok(_) => {}
+            }
+        };
+        println!(
+            "Go! The word has {} letters, and you have {} guesses. 🏁",
+            length, guesses
+        );
+
+        loop {
+            if !input.is_scripted() {
+                print!("Your guess: ");
+                stdout().flush().unwrap();
+                PROMPTING.store(true, Ordering::SeqCst);
+            }
+            let line = input.read_line();
+            PROMPTING.store(false, Ordering::SeqCst);
+            let Some(guess) = line.map(|l| l.to_lowercase()) else {
+                // No more input, give up the race. The host still tells us when it's over
+                if !input.is_scripted() {
+                    println!();
+                }
+                let _ = self.stream.shutdown(Shutdown::Write);
+                return;
+            };
+            if writeln!(&self.stream, "GUESS {}", guess).is_err() {
+                return;
+            }
+            match self.messages.recv() {
+                Ok(Message::Feedback(feedback)) => print_feedback(&guess, &feedback),
+                Ok(Message::Invalid(reason)) => println!("Not so fast, {}... 👀", reason),
+                Ok(Message::Start(..)) => {}
+                Ok(Message::Over) | Err(_) => return,
+            }
+        }
+    }
+
+    /// Waits for the host to say the race is over.
+    fn wait_until_over(&self) {
+        while let Ok(message) = self.messages.recv() {
+            if let Message::Over = message {
+                return;
+            }
+        }
+    }
+}
+
+/// Reads what the host sends. Progress of the others and the end of the game are shown right
+/// away, the rest goes to the player's own board.
+fn listen(reader: BufReader<TcpStream>, sender: Sender<Message>, finish: Finish) {
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match parts.as_slice() {
+            ["JOINED", name] => announce(&format!("{} joined the race", name)),
+            ["LEFT", name] => announce(&format!("{} left the race", name)),
+            ["START", length, guesses] => {
+                let _ = sender.send(Message::Start(
+                    length.parse().unwrap_or(0),
+                    guesses.parse().unwrap_or(0),
+                ));
+            }
+            ["FEEDBACK", pattern] => {
+                if let Ok(feedback) = parse_pattern(pattern) {
+                    let _ = sender.send(Message::Feedback(feedback));
+                }
+            }
+            ["INVALID", ..] => {
+                let _ = sender.send(Message::Invalid(parts[1..].join(" ")));
+            }
+            ["PROGRESS", name, pattern] => {
+                if let Ok(feedback) = parse_pattern(pattern) {
+                    let blanks = " ".repeat(feedback.len());
+                    announce(&format!("{:>12} {}", name, format_feedback(&blanks, &feedback)));
+                }
+            }
+            ["OUT"] => announce("You're out of guesses, let's see how the others do... 😬"),
+            ["WINNER", name, guesses] => {
+                announce(&format!("{} solved it in {} guesses! 🏆", name.bold(), guesses))
+            }
+            ["OVER", word] => {
+                PROMPTING.store(false, Ordering::SeqCst);
+                announce(&format!("The word was \"{}\"!", word));
+                let _ = sender.send(Message::Over);
+                return finish(0);
+            }
+            _ => debug_print(format!("Unknown message from the host: {}", line).as_str()),
+        }
+    }
+    PROMPTING.store(false, Ordering::SeqCst);
+    announce("Lost the connection to the host. 🔌");
+    finish(1);
+}
+
+/// Prints a line above the prompt, without messing up the board.
+fn announce(text: &str) {
+    let mut out = stdout();
+    let _ = out.execute(crossterm::cursor::MoveToColumn(0));
+    let _ = out.execute(terminal::Clear(ClearType::CurrentLine));
+    println!("{}", text);
+    if PROMPTING.load(Ordering::SeqCst) {
+        print!("Your guess: ");
+    }
+    let _ = out.flush();
+}
+
+pub fn join(address: &str, name: &str, input: &mut Input) -> Result<(), String> {
+    let connection = Connection::open(address, name, Box::new(|code| std::process::exit(code)))?;
+    println!("Joined the race at {}, waiting for the host to start... ⏳", address);
+    connection.play(input);
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+use colorful::{Color, Colorful};
+
+use crate::game::{Game, Turn};
+use crate::input::Input;
+use crate::print_feedback;
+use crate::scoring::{score, Feedback};
+
+const RECORDING_VERSION: u32 = 1;
+// Long thinks are cut short when replaying, nobody wants to watch that
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(3);
+
+// --- Recording games to files, and playing them back ---
+#[derive(Serialize, Deserialize)]
+pub struct Recording {
+    pub version: u32,
+    pub answer: String,
+    /// Seconds since the unix epoch
+    pub started: u64,
+    pub settings: Settings,
+    pub turns: Vec<RecordedTurn>,
+    pub result: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Settings {
+    pub guesses: u32,
+    /// "internal", or the path of the word list file
+    pub list: String,
+    pub mode: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RecordedTurn {
+    pub guess: String,
+    pub feedback: Vec<Feedback>,
+    /// Milliseconds since the start of the game
+    pub at: u64,
+}
+
+impl Recording {
+    pub fn from_game(game: &Game, list: &str, mode: &str) -> Recording {
+        Recording {
+            version: RECORDING_VERSION,
+            answer: game.word.clone(),
+            started: game
+                .started
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            settings: Settings {
+                guesses: game.guesses,
+                list: list.to_string(),
+                mode: mode.to_string(),
+            },
+            turns: game
+                .history
+                .iter()
+                .zip(&game.times)
+                .map(|(turn, time)| RecordedTurn {
+                    guess: turn.guess.clone(),
+                    feedback: turn.feedback.clone(),
+                    at: time.as_millis() as u64,
+                })
+                .collect(),
+            result: String::from(if game.is_won() {
+                "won"
+            } else if game.is_over() {
+                "lost"
+            } else {
+                "quit"
+            }),
+        }
+    }
+
+    /// Saves the recording in the folder, under a name made from the start time.
+    pub fn save_in(&self, dir: &str) -> Result<PathBuf, String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Can't create {}: {}", dir, e))?;
+        let mut path = Path::new(dir).join(format!("rustle-{}.json", self.started));
+        let mut n = 1;
+        while path.exists() {
+            n += 1;
+            path = Path::new(dir).join(format!("rustle-{}-{}.json", self.started, n));
+        }
+        let json = serde_json::to_string_pretty(self).unwrap();
+        fs::write(&path, json).map_err(|e| format!("Can't write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    pub fn load(path: &str) -> Result<Recording, String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+        let recording: Recording = serde_json::from_str(&json)
+            .map_err(|e| format!("{} isn't a rustle recording: {}", path, e))?;
+        if recording.version > RECORDING_VERSION {
+            return Err(format!("{} was recorded by a newer version of rustle", path));
+        }
+        Ok(recording)
+    }
+
+    pub fn history(&self) -> Vec<Turn> {
+        self.turns
+            .iter()
+            .map(|t| Turn {
+                guess: t.guess.clone(),
+                feedback: t.feedback.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Shows the game again, guess by guess. The time between guesses is divided by `speed`, or
+/// with `step` every guess waits for enter.
+pub fn replay(recording: &Recording, speed: f64, mut step: bool, input: &mut Input) {
+    println!(
+        "Replaying a game from the {} word list, {} letters and {} guesses.",
+        recording.settings.list,
+        recording.answer.chars().count(),
+        recording.settings.guesses
+    );
+    let mut previous = 0;
+    for turn in &recording.turns {
+        if step {
+            print!("{}", "Press enter for the next guess".color(Color::LightGray));
+            stdout().flush().unwrap();
+            // Without more input, the rest plays by itself
+            step = input.read_line().is_some();
+            if !step {
+                println!();
+            }
+        }
+        if !step {
+            let gap = turn.at.saturating_sub(previous) as f64 / 1000.0 / speed;
+            // Very slow speeds make gaps too long for a Duration
+            let gap = Duration::try_from_secs_f64(gap).unwrap_or(MAX_REPLAY_GAP);
+            thread::sleep(gap.min(MAX_REPLAY_GAP));
+        }
+
+        print!("{} ", format!("{:>7.1}s", turn.at as f64 / 1000.0).color(Color::LightGray));
+        print_feedback(&turn.guess, &turn.feedback);
+        // Handy for bug reports about scoring
+        let rescored = score(&recording.answer, &turn.guess);
+        if rescored != turn.feedback {
+            println!(" This version of rustle scores that guess differently:");
+            print!("          ");
+            print_feedback(&turn.guess, &rescored);
+        }
+        previous = turn.at;
+    }
+
+    match recording.result.as_str() {
+        "won" if recording.turns.len() == 1 => println!("Solved on the first try! 🍀"),
+        "won" => println!("Solved in {} guesses! 🥳", recording.turns.len()),
+        "lost" => println!("The word was \"{}\", not solved. 😔", recording.answer),
+        _ => println!("The game was left unfinished, the word was \"{}\".", recording.answer),
+    }
+}
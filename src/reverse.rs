@@ -0,0 +1,209 @@
+use std::io::{stdout, Write};
+
+use colorful::{Color, Colorful};
+use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::{cursor, terminal, ExecutableCommand};
+
+use crate::scoring::{is_consistent, parse_pattern, pattern_string, score, Feedback};
+use crate::game::Turn;
+use crate::input::{self, Input};
+use crate::solver::Solver;
+use crate::{format_feedback, print_feedback};
+
+// --- Reverse mode: the player thinks of a word and the solver guesses it ---
+pub fn play_reverse(solver: &mut dyn Solver, words: &[String], guesses: u32, input: &mut Input) -> Result<(), String> {
+    if words.is_empty() {
+        println!("There are no words of that length in the list. 🤷");
+        return Ok(());
+    }
+    let length = words[0].chars().count();
+    println!(
+        "Think of a word with {} letters, and I'll try to guess it in {} guesses. 🤔",
+        length, guesses
+    );
+    println!("Tell me how I did with a pattern like \"gy..g\" (G correct, Y misplaced, . wrong),");
+    println!("or press enter without typing anything to colour the tiles with the arrow keys.\n");
+
+    let mut candidates = words.to_vec();
+    let mut history: Vec<Turn> = Vec::new();
+    while (history.len() as u32) < guesses {
+        let guess = solver.next_guess(words, &candidates, &history)?;
+        let feedback = match read_feedback(&guess, input) {
+            Some(feedback) => feedback,
+            None => {
+                println!("\nNo more feedback, I'll never know your word. 😢");
+                return Ok(());
+            }
+        };
+        if feedback.iter().all(|f| *f == Feedback::Correct) {
+            match history.len() {
+                0 => println!("Got it on the first try! 🤖"),
+                n => println!("Got it in {} guesses! 🤖", n + 1),
+            }
+            return Ok(());
+        }
+
+        candidates.retain(|w| is_consistent(w, &guess, &feedback));
+        history.push(Turn { guess, feedback });
+        if candidates.is_empty() {
+            println!("No word in the list matches what you've told me. 🤨");
+            explain_contradiction(words, &history, input);
+            return Ok(());
+        }
+    }
+    println!("I'm out of guesses, you win! 🎉");
+    Ok(())
+}
+
+/// Asks for the feedback on a guess, either typed as a pattern or by colouring the tiles.
+/// `None` when the input ran out.
+fn read_feedback(guess: &str, input: &mut Input) -> Option<Vec<Feedback>> {
+    loop {
+        print!("My guess is {}, how did I do? ", guess.to_uppercase().bold());
+        stdout().flush().unwrap();
+        let line = input.read_line()?;
+
+        let feedback = if line.is_empty() {
+            toggle_feedback(guess)
+        } else {
+            match parse_pattern(&line) {
+                Ok(feedback) => feedback,
+                Err(message) => {
+                    println!("{}", message);
+                    continue;
+                }
+            }
+        };
+        if feedback.len() != guess.chars().count() {
+            println!("The pattern needs one character for each letter of the guess.");
+            continue;
+        }
+        print_feedback(guess, &feedback);
+        return Some(feedback);
+    }
+}
+
+/// Lets the player colour the tiles of the guess. Left and right pick a tile, up, down or space
+/// change its colour, and G, Y or . set it directly. Enter is done.
+fn toggle_feedback(guess: &str) -> Vec<Feedback> {
+    let letters: Vec<char> = guess.chars().collect();
+    let mut feedback = vec![Feedback::Wrong; letters.len()];
+    let mut selected = 0;
+
+    terminal::enable_raw_mode().unwrap();
+    loop {
+        let mut tiles = String::new();
+        for (n, letter) in letters.iter().enumerate() {
+            let tile = format_feedback(&letter.to_string(), &feedback[n..n + 1]);
+            if n == selected {
+                tiles += &format!("[{}]", tile);
+            } else {
+                tiles += &format!(" {} ", tile);
+            }
+        }
+        let mut out = stdout();
+        out.execute(cursor::MoveToColumn(0)).unwrap();
+        out.execute(terminal::Clear(terminal::ClearType::CurrentLine)).unwrap();
+        print!("{}  {}", tiles, "←→ tile, ↑↓ colour, enter when done".color(Color::LightGray));
+        out.flush().unwrap();
+
+        if let Event::Key(KeyEvent { code, modifiers }) = read().unwrap() {
+            match code {
+                KeyCode::Left => selected = selected.saturating_sub(1),
+                KeyCode::Right | KeyCode::Tab => selected = (selected + 1).min(letters.len() - 1),
+                KeyCode::Up | KeyCode::Char(' ') => feedback[selected] = next_colour(feedback[selected]),
+                KeyCode::Down => {
+                    feedback[selected] = next_colour(next_colour(feedback[selected]))
+                }
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => input::quit(130),
+                KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => input::quit(0),
+                KeyCode::Char(c) => {
+                    if let Ok(f) = parse_pattern(&c.to_string()) {
+                        feedback[selected] = f[0];
+                        selected = (selected + 1).min(letters.len() - 1);
+                    }
+                }
+                KeyCode::Enter => break,
+                _ => {}
+            }
+        }
+    }
+    terminal::disable_raw_mode().unwrap();
+    stdout().execute(cursor::MoveToColumn(0)).unwrap();
+    stdout()
+        .execute(terminal::Clear(terminal::ClearType::CurrentLine))
+        .unwrap();
+    feedback
+}
+
+fn next_colour(feedback: Feedback) -> Feedback {
+    match feedback {
+        Feedback::Wrong => Feedback::Misplaced,
+        Feedback::Misplaced => Feedback::Correct,
+        Feedback::Correct => Feedback::Wrong,
+    }
+}
+
+/// Figures out which feedback was wrong, by asking for the word or by trying to leave out one
+/// answer at a time.
+fn explain_contradiction(words: &[String], history: &[Turn], input: &mut Input) {
+    print!("What was your word? (press enter to skip): ");
+    stdout().flush().unwrap();
+    let word = input.read_line().unwrap_or_default().to_lowercase();
+
+    if !word.is_empty() {
+        if word.chars().count() != history[0].guess.chars().count() {
+            println!("That word doesn't have the right amount of letters. 🧐");
+            return;
+        }
+        let mut mistakes = 0;
+        for turn in history {
+            let correct = score(&word, &turn.guess);
+            if correct != turn.feedback {
+                mistakes += 1;
+                println!(
+                    "For {} you told me {}, but it should have been {}",
+                    turn.guess,
+                    pattern_string(&turn.feedback),
+                    pattern_string(&correct)
+                );
+                print_feedback(&turn.guess, &correct);
+            }
+        }
+        if mistakes == 0 {
+            println!("All your feedback was right, \"{}\" just isn't in my word list. 📖", word);
+        }
+        return;
+    }
+
+    let mut suspects = 0;
+    for (n, turn) in history.iter().enumerate() {
+        let others: Vec<&Turn> = history
+            .iter()
+            .enumerate()
+            .filter(|(m, _)| *m != n)
+            .map(|(_, t)| t)
+            .collect();
+        let matches: Vec<&String> = words
+            .iter()
+            .filter(|w| others.iter().all(|t| is_consistent(w, &t.guess, &t.feedback)))
+            .collect();
+        if !matches.is_empty() {
+            suspects += 1;
+            println!(
+                "Without your answer {} for {}, it could have been: {}",
+                pattern_string(&turn.feedback),
+                turn.guess,
+                matches
+                    .iter()
+                    .take(10)
+                    .map(|w| w.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+    if suspects == 0 {
+        println!("More than one answer must have been off, or your word isn't in my list.");
+    }
+}
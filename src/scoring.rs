@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+// --- Scoring rules, shared by the game and the solvers ---
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Feedback {
+    Correct,
+    Misplaced,
+    Wrong,
+}
+
+/// Scores a guess against the answer. Correct letters are claimed first, then misplaced letters are
+/// handed out left to right while unclaimed copies of that letter remain in the answer.
+pub fn score(answer: &str, guess: &str) -> Vec<Feedback> {
+    let answer: Vec<char> = answer.chars().collect();
+    let guess: Vec<char> = guess.chars().collect();
+
+    let mut result = vec![Feedback::Wrong; guess.len()];
+    let mut available_letters = vec![true; answer.len()];
+    for (n, input_char) in guess.iter().enumerate() {
+        if answer.get(n) == Some(input_char) {
+            result[n] = Feedback::Correct;
+            available_letters[n] = false;
+        }
+    }
+    for (n, input_char) in guess.iter().enumerate() {
+        if result[n] == Feedback::Correct {
+            continue;
+        }
+        for (m, word_char) in answer.iter().enumerate() {
+            if available_letters[m] && word_char == input_char {
+                // unclaimed matching letter
+                result[n] = Feedback::Misplaced;
+                available_letters[m] = false;
+                break;
+            }
+        }
+    }
+    result
+}
+
+/// Whether `word` could still be the answer, given that `guess` received `feedback`.
+pub fn is_consistent(word: &str, guess: &str, feedback: &[Feedback]) -> bool {
+    word.chars().count() == feedback.len() && score(word, guess) == feedback
+}
+
+/// Feedback as a pattern string like `GY..G`: G for correct, Y for misplaced and . for wrong.
+pub fn pattern_string(feedback: &[Feedback]) -> String {
+    feedback
+        .iter()
+        .map(|f| match f {
+            Feedback::Correct => 'G',
+            Feedback::Misplaced => 'Y',
+            Feedback::Wrong => '.',
+        })
+        .collect()
+}
+
+/// Feedback as coloured squares, the way Wordle results get shared.
+pub fn symbol_string(feedback: &[Feedback]) -> String {
+    feedback
+        .iter()
+        .map(|f| match f {
+            Feedback::Correct => '🟩',
+            Feedback::Misplaced => '🟨',
+            Feedback::Wrong => '⬛',
+        })
+        .collect()
+}
+
+/// Reads a pattern string like `GY..G` or `gy--g`. Wrong letters may be written as `.`, `-`,
+/// `_` or `x`.
+pub fn parse_pattern(pattern: &str) -> Result<Vec<Feedback>, String> {
+    pattern
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            'g' => Ok(Feedback::Correct),
+            'y' => Ok(Feedback::Misplaced),
+            '.' | '-' | '_' | 'x' => Ok(Feedback::Wrong),
+            _ => Err(format!(
+                "Unknown character '{}' in pattern \"{}\", use G, Y or .",
+                c, pattern
+            )),
+        })
+        .collect()
+}
+
+/// Feedback packed into a number, one base-3 digit per letter with the first letter as the least
+/// significant digit. Wrong is 0, misplaced is 1 and correct is 2.
+pub type Pattern = u64;
+
+pub fn encode(feedback: &[Feedback]) -> Pattern {
+    feedback.iter().rev().fold(0, |pattern, f| {
+        pattern * 3
+            + match f {
+                Feedback::Wrong => 0,
+                Feedback::Misplaced => 1,
+                Feedback::Correct => 2,
+            }
+    })
+}
+
+/// The pattern a guess gets against the answer, see `score`.
+pub fn score_pattern(answer: &str, guess: &str) -> Pattern {
+    encode(&score(answer, guess))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(answer: &str, guess: &str) -> String {
+        pattern_string(&score(answer, guess))
+    }
+
+    #[test]
+    fn repeated_letters() {
+        assert_eq!(pattern("abbey", "babes"), "YYGG.");
+        assert_eq!(pattern("babes", "abbey"), "YYGG.");
+        assert_eq!(pattern("crane", "nacre"), "YYYYG");
+        // Only one e in the answer, and the correct one claims it
+        assert_eq!(pattern("crane", "eerie"), "..Y.G");
+    }
+
+    #[test]
+    fn pattern_round_trip() {
+        let feedback = score("abbey", "babes");
+        assert_eq!(parse_pattern(&pattern_string(&feedback)).unwrap(), feedback);
+        assert_eq!(parse_pattern("gy-x_").unwrap(), parse_pattern("GY...").unwrap());
+        assert!(parse_pattern("GYZ..").is_err());
+    }
+
+    #[test]
+    fn encoded_patterns() {
+        assert_eq!(encode(&parse_pattern(".....").unwrap()), 0);
+        // The first letter is the least significant digit
+        assert_eq!(encode(&parse_pattern("Y....").unwrap()), 1);
+        assert_eq!(encode(&parse_pattern("G....").unwrap()), 2);
+        assert_eq!(encode(&parse_pattern(".Y...").unwrap()), 3);
+        assert_eq!(encode(&parse_pattern("GGGGG").unwrap()), 3u64.pow(5) - 1);
+        assert_eq!(score_pattern("abbey", "babes"), encode(&score("abbey", "babes")));
+    }
+}
@@ -0,0 +1,265 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::game::{Game, GuessError, Turn};
+use crate::scoring::pattern_string;
+use crate::{debug_print, load_word_list};
+
+// --- HTTP server, playing the same game over a JSON API ---
+//
+//   POST /games                 {"length", "list", "guesses", "mode", "seed"}, all optional
+//   GET  /games/<id>            the state of the game
+//   POST /games/<id>/guesses    {"guess": "crane"}
+//   POST /games/<id>/finish     gives up, or closes a game that's over, and reveals the answer
+//   GET  /lists                 the word lists that games can use
+//
+// The answer is only ever part of a response once the game is over.
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewGame {
+    length: Option<usize>,
+    list: Option<String>,
+    guesses: Option<u32>,
+    mode: Option<String>,
+    seed: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct NewGuess {
+    guess: String,
+}
+
+#[derive(Serialize)]
+struct TurnState<'a> {
+    guess: &'a str,
+    feedback: &'a [crate::scoring::Feedback],
+    pattern: String,
+}
+
+struct ServerGame {
+    game: Game,
+    list: String,
+    finished: bool,
+}
+
+impl ServerGame {
+    fn state(&self, id: &str) -> serde_json::Value {
+        let over = self.finished || self.game.is_over();
+        let status = if self.game.is_won() {
+            "won"
+        } else if self.game.guesses_left() == 0 {
+            "lost"
+        } else if self.finished {
+            "finished"
+        } else {
+            "playing"
+        };
+        json!({
+            "id": id,
+            "list": self.list,
+            "length": self.game.length(),
+            "guesses": self.game.guesses,
+            "guesses_left": self.game.guesses_left(),
+            "status": status,
+            "history": self.game.history.iter().map(turn_state).collect::<Vec<_>>(),
+            "answer": if over { Some(&self.game.word) } else { None },
+        })
+    }
+}
+
+fn turn_state(turn: &Turn) -> TurnState<'_> {
+    TurnState {
+        guess: &turn.guess,
+        feedback: &turn.feedback,
+        pattern: pattern_string(&turn.feedback),
+    }
+}
+
+struct ApiError(u16, String);
+
+fn error(status: u16, message: impl Into<String>) -> ApiError {
+    ApiError(status, message.into())
+}
+
+pub struct GameServer {
+    lists: BTreeMap<String, Vec<String>>,
+    games: HashMap<String, ServerGame>,
+    default_guesses: u32,
+    /// Words that are never picked as the answer, from --family-friendly and --blocklist
+    blocked: HashSet<String>,
+}
+
+impl GameServer {
+    /// Serves the internal list, the list file if one was given, and every `.txt` file in the
+    /// lists folder, named after the file without its extension. `blocked` words are never picked.
+    pub fn new(
+        internal: Vec<String>,
+        list_file: Option<(&str, &str)>,
+        lists_dir: Option<&str>,
+        default_guesses: u32,
+        blocked: HashSet<String>,
+    ) -> Result<GameServer, String> {
+        let mut lists = BTreeMap::new();
+        lists.insert(String::from("internal"), internal);
+        if let Some((path, separator)) = list_file {
+            lists.insert(String::from("file"), load_word_list(path, separator));
+        }
+        if let Some(dir) = lists_dir {
+            let entries = fs::read_dir(dir).map_err(|e| format!("Can't read {}: {}", dir, e))?;
+            for path in entries.flatten().map(|e| e.path()) {
+                if path.extension().is_some_and(|e| e == "txt") {
+                    let name = path.file_stem().unwrap().to_string_lossy().to_string();
+                    lists.insert(name, load_word_list(&path.to_string_lossy(), "\n"));
+                }
+            }
+        }
+        Ok(GameServer {
+            lists,
+            games: HashMap::new(),
+            default_guesses,
+            blocked,
+        })
+    }
+
+    pub fn run(&mut self, port: u16) -> Result<(), String> {
+        let server = Server::http(("0.0.0.0", port))
+            .map_err(|e| format!("Can't listen on port {}: {}", port, e))?;
+        println!(
+            "Serving games on http://localhost:{}/ with the lists: {}",
+            port,
+            self.lists.keys().cloned().collect::<Vec<_>>().join(", ")
+        );
+        for request in server.incoming_requests() {
+            self.handle(request);
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, mut request: Request) {
+        debug_print(format!("{} {}", request.method(), request.url()).as_str());
+        let (status, body) = if *request.method() == Method::Options {
+            // Preflight for web pages on other origins
+            (204, String::new())
+        } else {
+            let mut body = String::new();
+            let result = match request.as_reader().read_to_string(&mut body) {
+                Ok(_) => self.route(request.method(), request.url(), &body),
+                Err(_) => Err(error(400, "The request body isn't valid UTF-8")),
+            };
+            match result {
+                Ok((status, value)) => (status, value.to_string()),
+                Err(ApiError(status, message)) => (status, json!({ "error": message }).to_string()),
+            }
+        };
+
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(header("Content-Type", "application/json"))
+            .with_header(header("Access-Control-Allow-Origin", "*"))
+            .with_header(header("Access-Control-Allow-Headers", "Content-Type"))
+            .with_header(header("Access-Control-Allow-Methods", "GET, POST, OPTIONS"));
+        let _ = request.respond(response);
+    }
+
+    fn route(&mut self, method: &Method, url: &str, body: &str) -> Result<(u16, serde_json::Value), ApiError> {
+        let path = url.split('?').next().unwrap_or("");
+        let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+        match (method, parts.as_slice()) {
+            (Method::Get, ["lists"]) => Ok((
+                200,
+                json!(self
+                    .lists
+                    .iter()
+                    .map(|(name, words)| json!({ "name": name, "words": words.len() }))
+                    .collect::<Vec<_>>()),
+            )),
+            (Method::Post, ["games"]) => self.create(parse_body(body)?),
+            (Method::Get, ["games", id]) => Ok((200, self.game(id)?.state(id))),
+            (Method::Post, ["games", id, "guesses"]) => self.guess(id, parse_body(body)?),
+            (Method::Post, ["games", id, "finish"]) => {
+                let game = self.game(id)?;
+                game.finished = true;
+                Ok((200, game.state(id)))
+            }
+            _ => Err(error(404, format!("There's nothing at {} {}", method, path))),
+        }
+    }
+
+    fn game(&mut self, id: &str) -> Result<&mut ServerGame, ApiError> {
+        self.games
+            .get_mut(id)
+            .ok_or_else(|| error(404, format!("There's no game with id \"{}\"", id)))
+    }
+
+    fn create(&mut self, options: NewGame) -> Result<(u16, serde_json::Value), ApiError> {
+        let mode = options.mode.as_deref().unwrap_or("classic");
+        if mode != "classic" {
+            return Err(error(400, format!("Unknown mode \"{}\", the only mode is \"classic\"", mode)));
+        }
+        let list = options.list.unwrap_or_else(|| String::from("internal"));
+        let words = self
+            .lists
+            .get(&list)
+            .ok_or_else(|| error(400, format!("Unknown word list \"{}\"", list)))?;
+        let words: Vec<&String> = words
+            .iter()
+            .filter(|w| !self.blocked.contains(&w.to_lowercase()))
+            .filter(|w| options.length.is_none_or(|length| w.chars().count() == length))
+            .collect();
+        if words.is_empty() {
+            return Err(error(400, format!("The list \"{}\" has no words to pick of that length", list)));
+        }
+        let guesses = options.guesses.unwrap_or(self.default_guesses);
+        if guesses == 0 {
+            return Err(error(400, "A game needs at least one guess"));
+        }
+
+        let mut rng = match options.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let word = words[rng.gen_range(0..words.len())].to_lowercase();
+        let id = format!("{:016x}", rand::thread_rng().gen::<u64>());
+        let game = ServerGame {
+            game: Game::new(&word, guesses),
+            list,
+            finished: false,
+        };
+        let state = game.state(&id);
+        self.games.insert(id, game);
+        Ok((201, state))
+    }
+
+    fn guess(&mut self, id: &str, guess: NewGuess) -> Result<(u16, serde_json::Value), ApiError> {
+        let game = self.game(id)?;
+        if game.finished {
+            return Err(error(409, "The game is over"));
+        }
+        let result = game.game.guess(&guess.guess.trim().to_lowercase());
+        match result {
+            Ok(_) => {
+                let turn = turn_state(game.game.history.last().unwrap());
+                let value = json!({ "turn": turn, "game": game.state(id) });
+                Ok((200, value))
+            }
+            Err(e @ GuessError::WrongLength { .. }) => Err(error(400, e.to_string())),
+            Err(e @ GuessError::Over) => Err(error(409, e.to_string())),
+        }
+    }
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, ApiError> {
+    let body = if body.trim().is_empty() { "{}" } else { body };
+    serde_json::from_str(body).map_err(|e| error(400, format!("Invalid JSON: {}", e)))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap()
+}
@@ -0,0 +1,354 @@
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Arc;
+
+use crate::game::Turn;
+use crate::patterns::{PatternMatrix, MAX_MATRIX_LENGTH};
+use crate::scoring::{is_consistent, pattern_string, score, score_pattern, Pattern};
+
+pub const STRATEGY_NAMES: [&str; 4] = ["random", "frequency", "entropy", "minimax"];
+
+// --- Strategies for picking the next guess ---
+pub trait Solver {
+    /// Picks the next guess. `words` are all valid words of the answer's length, `candidates` are
+    /// the ones that are still consistent with the `history` of guesses so far. Only external
+    /// solvers can fail.
+    fn next_guess(&mut self, words: &[String], candidates: &[String], history: &[Turn]) -> Result<String, String>;
+
+    /// Hands the solver precomputed patterns for the word list, for the solvers that can use them.
+    fn use_matrix(&mut self, _matrix: Arc<PatternMatrix>) {}
+}
+
+/// Creates a built-in solver by name, or an external one when the name is `exec:<command>`.
+pub fn by_name(name: &str) -> Result<Box<dyn Solver>, String> {
+    if let Some(command) = name.strip_prefix("exec:") {
+        return Ok(Box::new(ExternalSolver::spawn(command)?));
+    }
+    match name.to_lowercase().as_str() {
+        "random" => Ok(Box::new(RandomSolver)),
+        "frequency" => Ok(Box::new(FrequencySolver)),
+        "entropy" => Ok(Box::new(EntropySolver::default())),
+        "minimax" => Ok(Box::new(MinimaxSolver::default())),
+        _ => Err(format!(
+            "Unknown strategy \"{}\", pick one of: {}, or exec:<command>",
+            name,
+            STRATEGY_NAMES.join(", ")
+        )),
+    }
+}
+
+/// Creates a solver like `by_name`, and hands it a pattern matrix for `words` if `matrix` is set.
+/// The words must all have the same length.
+pub fn prepare(name: &str, words: &[String], matrix: bool) -> Result<Box<dyn Solver>, String> {
+    let mut solver = by_name(name)?;
+    if let Some(matrix) = matrix_for(words, matrix) {
+        solver.use_matrix(matrix);
+    }
+    Ok(solver)
+}
+
+/// Loads the pattern matrix for `words` if it's wanted, and if the words aren't too long for it.
+pub fn matrix_for(words: &[String], wanted: bool) -> Option<Arc<PatternMatrix>> {
+    if !wanted || words.is_empty() {
+        return None;
+    }
+    if words[0].chars().count() > MAX_MATRIX_LENGTH {
+        println!(
+            "Words longer than {} letters don't fit in a pattern matrix, going without.",
+            MAX_MATRIX_LENGTH
+        );
+        return None;
+    }
+    Some(Arc::new(PatternMatrix::load_or_build(words)))
+}
+
+/// The words that have the given amount of letters.
+pub fn words_of_length(words: &[String], length: usize) -> Vec<String> {
+    words
+        .iter()
+        .filter(|w| w.chars().count() == length)
+        .cloned()
+        .collect()
+}
+
+/// Guesses any word that could still be the answer.
+pub struct RandomSolver;
+
+impl Solver for RandomSolver {
+    fn next_guess(&mut self, _words: &[String], candidates: &[String], _history: &[Turn]) -> Result<String, String> {
+        let mut rng = rand::thread_rng();
+        Ok(candidates[rng.gen_range(0..candidates.len())].clone())
+    }
+}
+
+/// Guesses the candidate made of letters that show up in as many candidates as possible.
+pub struct FrequencySolver;
+
+impl Solver for FrequencySolver {
+    fn next_guess(&mut self, _words: &[String], candidates: &[String], _history: &[Turn]) -> Result<String, String> {
+        let value = letter_frequency(candidates);
+        Ok(candidates.iter().max_by_key(|w| value(w)).unwrap().clone())
+    }
+}
+
+/// Scores words by how many candidates have each of their letters, added up.
+pub fn letter_frequency(candidates: &[String]) -> impl Fn(&str) -> usize {
+    let mut counts = [0usize; 128];
+    for word in candidates {
+        let letters = letter_set(word);
+        for (c, count) in counts.iter_mut().enumerate() {
+            if letters & (1 << c) != 0 {
+                *count += 1;
+            }
+        }
+    }
+    move |word: &str| -> usize {
+        let letters = letter_set(word);
+        (0..128).filter(|c| letters & (1 << c) != 0).map(|c| counts[c]).sum()
+    }
+}
+
+/// The distinct ASCII characters of a word, as a bit set.
+fn letter_set(word: &str) -> u128 {
+    word.chars()
+        .filter(char::is_ascii)
+        .fold(0, |set, c| set | 1 << c as u32)
+}
+
+/// Guesses the word that is expected to tell the most about the answer, in bits.
+#[derive(Default)]
+pub struct EntropySolver {
+    opening: Option<String>,
+    matrix: Option<Arc<PatternMatrix>>,
+}
+
+impl Solver for EntropySolver {
+    fn next_guess(&mut self, words: &[String], candidates: &[String], history: &[Turn]) -> Result<String, String> {
+        if history.is_empty() {
+            // The opening only depends on the word list, so it's worth remembering
+            if let Some(opening) = remembered_opening(&self.opening, words) {
+                return Ok(opening);
+            }
+        }
+        let total = candidates.len() as f64;
+        let guess = best_guess(words, candidates, self.matrix.as_deref(), |groups| {
+            -groups
+                .map(|size| {
+                    let p = size as f64 / total;
+                    -p * p.log2()
+                })
+                .sum::<f64>()
+        });
+        if history.is_empty() {
+            self.opening = Some(guess.clone());
+        }
+        Ok(guess)
+    }
+
+    fn use_matrix(&mut self, matrix: Arc<PatternMatrix>) {
+        self.matrix = Some(matrix);
+    }
+}
+
+/// Guesses the word that leaves the fewest candidates in the worst case.
+#[derive(Default)]
+pub struct MinimaxSolver {
+    opening: Option<String>,
+    matrix: Option<Arc<PatternMatrix>>,
+}
+
+impl Solver for MinimaxSolver {
+    fn next_guess(&mut self, words: &[String], candidates: &[String], history: &[Turn]) -> Result<String, String> {
+        if history.is_empty() {
+            if let Some(opening) = remembered_opening(&self.opening, words) {
+                return Ok(opening);
+            }
+        }
+        let guess = best_guess(words, candidates, self.matrix.as_deref(), |groups| {
+            groups.max().unwrap_or(0) as f64
+        });
+        if history.is_empty() {
+            self.opening = Some(guess.clone());
+        }
+        Ok(guess)
+    }
+
+    fn use_matrix(&mut self, matrix: Arc<PatternMatrix>) {
+        self.matrix = Some(matrix);
+    }
+}
+
+fn remembered_opening(opening: &Option<String>, words: &[String]) -> Option<String> {
+    opening
+        .as_ref()
+        .filter(|o| o.chars().count() == words[0].chars().count())
+        .cloned()
+}
+
+/// Finds the guess with the lowest cost, where the cost is computed from the sizes of the groups
+/// the guess would split the candidates into. On ties, words that could be the answer win.
+fn best_guess<F>(
+    words: &[String],
+    candidates: &[String],
+    matrix: Option<&PatternMatrix>,
+    cost: F,
+) -> String
+where
+    F: Fn(&mut dyn Iterator<Item = usize>) -> f64,
+{
+    if candidates.len() <= 2 {
+        return candidates[0].clone();
+    }
+    let candidate_set: HashSet<&String> = candidates.iter().collect();
+
+    // When the matrix knows all the words, the patterns are just lookups
+    let lookup = matrix.and_then(|matrix| {
+        let guesses: Option<Vec<usize>> = words.iter().map(|w| matrix.index_of(w)).collect();
+        let answers: Option<Vec<usize>> = candidates.iter().map(|w| matrix.index_of(w)).collect();
+        Some((matrix, guesses?, answers?))
+    });
+    let mut counts: Vec<usize> = Vec::new();
+    let mut seen: Vec<Pattern> = Vec::new();
+    // Without the matrix, the index of every pattern's count. The counts are kept in the order
+    // the patterns were first seen, like with the matrix, so the float sums come out the same
+    // every run
+    let mut groups: HashMap<Pattern, usize> = HashMap::new();
+    let mut sizes: Vec<usize> = Vec::new();
+
+    let mut best: Option<(f64, bool, &String)> = None;
+    for (i, guess) in words.iter().enumerate() {
+        let value = match &lookup {
+            Some((matrix, guesses, answers)) => {
+                if counts.is_empty() {
+                    counts = vec![0; 3usize.pow(guess.chars().count() as u32)];
+                }
+                for &answer in answers {
+                    let pattern = matrix.get(guesses[i], answer);
+                    if counts[pattern as usize] == 0 {
+                        seen.push(pattern);
+                    }
+                    counts[pattern as usize] += 1;
+                }
+                let value = cost(&mut seen.iter().map(|p| counts[*p as usize]));
+                for pattern in seen.drain(..) {
+                    counts[pattern as usize] = 0;
+                }
+                value
+            }
+            None => {
+                groups.clear();
+                sizes.clear();
+                for answer in candidates {
+                    let index = *groups.entry(score_pattern(answer, guess)).or_insert_with(|| {
+                        sizes.push(0);
+                        sizes.len() - 1
+                    });
+                    sizes[index] += 1;
+                }
+                cost(&mut sizes.iter().copied())
+            }
+        };
+        let is_candidate = candidate_set.contains(guess);
+        let better = match best {
+            None => true,
+            Some((best_value, best_is_candidate, _)) => {
+                value < best_value || (value == best_value && is_candidate && !best_is_candidate)
+            }
+        };
+        if better {
+            best = Some((value, is_candidate, guess));
+        }
+    }
+    best.unwrap().2.clone()
+}
+
+/// Runs a program that picks the guesses, speaking a line based protocol over stdin and stdout.
+/// For every guess, rustle sends one line with the word length followed by the guesses so far
+/// and their feedback patterns, like `5 crane:..Y.G moist:.G..Y`, and reads back one line with
+/// the next guess.
+pub struct ExternalSolver {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ExternalSolver {
+    pub fn spawn(command: &str) -> Result<ExternalSolver, String> {
+        let mut parts = command.split_whitespace();
+        let program = parts.next().ok_or("No command given for the external solver")?;
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Error starting \"{}\": {}", command, e))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Ok(ExternalSolver {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+}
+
+impl Solver for ExternalSolver {
+    fn next_guess(&mut self, words: &[String], _candidates: &[String], history: &[Turn]) -> Result<String, String> {
+        let mut request = words[0].chars().count().to_string();
+        for turn in history {
+            request += &format!(" {}:{}", turn.guess, pattern_string(&turn.feedback));
+        }
+        let length = words[0].chars().count();
+        writeln!(self.stdin, "{}", request)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("Error writing to the external solver: {}", e))?;
+
+        let mut guess = String::new();
+        let read = self
+            .stdout
+            .read_line(&mut guess)
+            .map_err(|e| format!("Error reading from the external solver: {}", e))?;
+        if read == 0 {
+            return Err(String::from("The external solver stopped without making a guess"));
+        }
+        let guess = guess.trim().to_lowercase();
+        if guess.chars().count() != length {
+            return Err(format!(
+                "The external solver guessed \"{}\", but the word has {} letters",
+                guess, length
+            ));
+        }
+        Ok(guess)
+    }
+}
+
+impl Drop for ExternalSolver {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Lets the solver play against a known answer and returns how many guesses it needed.
+/// Gives up after `limit` guesses, returning `None`.
+pub fn solve(solver: &mut dyn Solver, answer: &str, words: &[String], limit: u32) -> Result<Option<u32>, String> {
+    let mut words = words_of_length(words, answer.chars().count());
+    if !words.iter().any(|w| w == answer) {
+        words.push(answer.to_string());
+    }
+    let mut candidates = words.clone();
+    let mut history: Vec<Turn> = Vec::new();
+
+    while (history.len() as u32) < limit {
+        let guess = solver.next_guess(&words, &candidates, &history)?;
+        if guess == answer {
+            return Ok(Some(history.len() as u32 + 1));
+        }
+        let feedback = score(answer, &guess);
+        candidates.retain(|w| is_consistent(w, &guess, &feedback));
+        history.push(Turn { guess, feedback });
+    }
+    Ok(None)
+}
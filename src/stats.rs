@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use colorful::{Color, Colorful};
+
+use crate::game::Game;
+
+// --- Stats kept between sessions, in a JSON file in the data folder ---
+//
+// The personal bests are the fastest wins for every word length and mode. They keep the time
+// of every guess, to compare the next games against while they're played. The high scores are
+// the longest survival runs.
+
+const MAX_HIGH_SCORES: usize = 10;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Stats {
+    #[serde(default)]
+    pub bests: Vec<Best>,
+    #[serde(default)]
+    pub high_scores: Vec<HighScore>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Best {
+    pub length: usize,
+    pub mode: String,
+    pub word: String,
+    /// Milliseconds from the start of the game to every guess, the last one is the total
+    pub splits: Vec<u64>,
+    /// Seconds since the unix epoch
+    pub date: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HighScore {
+    /// How many words were found before the run ended
+    pub words: u32,
+    pub length: usize,
+    /// Seconds since the unix epoch
+    pub date: u64,
+}
+
+impl Best {
+    pub fn time(&self) -> Duration {
+        Duration::from_millis(self.splits.last().copied().unwrap_or(0))
+    }
+}
+
+/// The stats file given with --stats, or the one in rustle's data folder.
+pub fn path(custom: Option<&str>) -> Result<PathBuf, String> {
+    match custom {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => dirs::data_dir()
+            .map(|dir| dir.join("rustle").join("stats.json"))
+            .ok_or_else(|| String::from("Can't find the data folder, use --stats to say where to keep the stats")),
+    }
+}
+
+impl Stats {
+    /// The stats in the file, or none yet if there is no file.
+    pub fn load(path: &Path) -> Result<Stats, String> {
+        if !path.exists() {
+            return Ok(Stats::default());
+        }
+        let json = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+        serde_json::from_str(&json).map_err(|e| format!("{} isn't a rustle stats file: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| format!("Can't create {}: {}", dir.display(), e))?;
+        }
+        let json = serde_json::to_string_pretty(self).unwrap();
+        fs::write(path, json).map_err(|e| format!("Can't write {}: {}", path.display(), e))
+    }
+
+    pub fn best(&self, length: usize, mode: &str) -> Option<&Best> {
+        self.bests.iter().find(|b| b.length == length && b.mode == mode)
+    }
+
+    /// Keeps the game if it's a win faster than the best so far, and returns whether it was.
+    pub fn add_game(&mut self, game: &Game, mode: &str) -> bool {
+        let Some(&time) = game.times.last().filter(|_| game.is_won()) else {
+            return false;
+        };
+        if self.best(game.length(), mode).is_some_and(|b| b.time() <= time) {
+            return false;
+        }
+        self.bests.retain(|b| b.length != game.length() || b.mode != mode);
+        self.bests.push(Best {
+            length: game.length(),
+            mode: mode.to_string(),
+            word: game.word.clone(),
+            splits: game.times.iter().map(|t| t.as_millis() as u64).collect(),
+            date: game.started.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        });
+        self.bests.sort_by(|a, b| (&a.mode, a.length).cmp(&(&b.mode, b.length)));
+        true
+    }
+
+    /// Adds the run to the high scores, and returns its place if it made it into them.
+    pub fn add_high_score(&mut self, words: u32, length: usize) -> Option<usize> {
+        let place = self.high_scores.iter().take_while(|s| s.words >= words).count();
+        if place >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.high_scores.insert(
+            place,
+            HighScore {
+                words,
+                length,
+                date: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            },
+        );
+        self.high_scores.truncate(MAX_HIGH_SCORES);
+        Some(place)
+    }
+}
+
+/// Minutes, seconds and tenths.
+pub fn format_time(time: Duration) -> String {
+    let tenths = time.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+/// How far ahead of the best (green) or behind it (red) the time is.
+pub fn format_difference(time: Duration, best: Duration) -> String {
+    if time <= best {
+        format!("-{:.1}s", (best - time).as_secs_f64()).color(Color::Green).to_string()
+    } else {
+        format!("+{:.1}s", (time - best).as_secs_f64()).color(Color::Red).to_string()
+    }
+}
+
+/// Prints all the personal bests and high scores.
+pub fn show(path: &Path) -> Result<(), String> {
+    let stats = Stats::load(path)?;
+    if stats.bests.is_empty() && stats.high_scores.is_empty() {
+        println!("No stats yet, play with --speedrun or --survival to set some. ⏱️");
+        return Ok(());
+    }
+    if !stats.high_scores.is_empty() {
+        println!("Survival high scores 💪");
+        for (place, score) in stats.high_scores.iter().enumerate() {
+            println!("{:>4}. {} words of {} letters", place + 1, score.words, score.length);
+        }
+    }
+    if stats.bests.is_empty() {
+        return Ok(());
+    }
+    println!("Personal bests 🏆");
+    for best in &stats.bests {
+        println!(
+            "{:>4} letters, {:<8} {}  \"{}\" in {} guesses",
+            best.length,
+            best.mode,
+            format_time(best.time()),
+            best.word,
+            best.splits.len()
+        );
+    }
+    Ok(())
+}